@@ -0,0 +1,715 @@
+//! A compact binary format for shipping a [`Dir`] as a single blob.
+//!
+//! All integers are little-endian. An archive starts with a 16 byte header:
+//!
+//! | Offset | Type      | Description                                      |
+//! |--------|-----------|--------------------------------------------------|
+//! | 0      | `[u8; 4]` | The magic bytes, `IDAR`                          |
+//! | 4      | `u16`     | The format version (currently `1`)               |
+//! | 6      | `u16`     | Flags (`1` = hashes, `2` = metadata)             |
+//! | 8      | `u32`     | The number of entries at the top level           |
+//! | 12     | `u32`     | The total number of entries                      |
+//!
+//! This is followed by a table with one fixed-size record per entry, in
+//! breadth-first order so the children of a directory are always contiguous
+//! and the top-level entries come first. Each directory's children must
+//! immediately follow the children of the directory before it, so every
+//! entry has exactly one parent:
+//!
+//! | Offset | Type  | Description                                              |
+//! |--------|-------|----------------------------------------------------------|
+//! | 0      | `u8`  | The kind of entry (`0` = directory, `1` = file)          |
+//! | 1      | `u8`  | `1` if the entry has metadata, otherwise `0`             |
+//! | 2      | `u16` | Reserved                                                 |
+//! | 4      | `u32` | The length of the entry's path                           |
+//! | 8      | `u64` | The offset of the entry's path                           |
+//! | 16     | `u64` | A file's data offset, or the index of a dir's first child |
+//! | 24     | `u64` | A file's length, or the number of children in a dir      |
+//! | 32     | `u64` | The FNV-1a hash of a file's contents (if flag `1` is set)  |
+//! | 32/40  | `[u8; 40]` | The entry's metadata (if flag `2` is set)          |
+//!
+//! Each field of the metadata is only meaningful if its bit is set in the
+//! first field:
+//!
+//! | Offset | Type  | Description                                              |
+//! |--------|-------|----------------------------------------------------------|
+//! | 0      | `u32` | Which fields are present (`1` = accessed, `2` = created, `4` = modified, `8` = mode, `16` = owner) |
+//! | 4      | `u32` | The Unix mode                                            |
+//! | 8      | `u32` | The owner's user ID                                      |
+//! | 12     | `u32` | The owner's group ID                                     |
+//! | 16     | `u64` | The accessed time, as nanoseconds since the Unix epoch   |
+//! | 24     | `u64` | The created time, as nanoseconds since the Unix epoch    |
+//! | 32     | `u64` | The modified time, as nanoseconds since the Unix epoch   |
+//!
+//! A file's size is always the length of its contents, and the root
+//! directory has no record so its metadata isn't stored.
+//!
+//! Paths and file contents are stored after the table, and all offsets are
+//! relative to the start of the archive.
+
+use crate::{Dir, DirEntry};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+
+const MAGIC: &[u8; 4] = b"IDAR";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 16;
+const RECORD_LEN: usize = 32;
+
+const FLAG_HASHES: u16 = 1;
+const FLAG_METADATA: u16 = 2;
+
+const METADATA_LEN: usize = 40;
+#[cfg(feature = "metadata")]
+const HAS_ACCESSED: u32 = 1;
+#[cfg(feature = "metadata")]
+const HAS_CREATED: u32 = 2;
+#[cfg(feature = "metadata")]
+const HAS_MODIFIED: u32 = 4;
+#[cfg(feature = "metadata")]
+const HAS_MODE: u32 = 8;
+#[cfg(feature = "metadata")]
+const HAS_OWNER: u32 = 16;
+
+const KIND_DIR: u8 = 0;
+const KIND_FILE: u8 = 1;
+
+impl<'a> Dir<'a> {
+    /// Serialize this directory using the archive format understood by
+    /// [`ArchiveDir::from_bytes()`].
+    ///
+    /// ```rust
+    /// use include_dir::{include_dir, ArchiveDir, Dir};
+    ///
+    /// static PROJECT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR");
+    ///
+    /// let bytes = PROJECT_DIR.to_archive_bytes();
+    /// let archive = ArchiveDir::from_bytes(&bytes).unwrap();
+    ///
+    /// let lib_rs = archive.get_file("src/lib.rs").unwrap();
+    /// assert_eq!(lib_rs.contents(), PROJECT_DIR.get_file("src/lib.rs").unwrap().contents());
+    /// ```
+    pub fn to_archive_bytes(&self) -> Vec<u8> {
+        // lay the entries out breadth-first, remembering where each
+        // directory's children start
+        let mut entries: Vec<&DirEntry<'a>> = self.entries().iter().collect();
+        let mut children = Vec::new();
+        let mut i = 0;
+
+        while i < entries.len() {
+            let range = match entries[i] {
+                DirEntry::Dir(d) => {
+                    let first = entries.len();
+                    entries.extend(d.entries());
+                    (first as u64, d.entries().len() as u64)
+                }
+                DirEntry::File(f) => (0, f.contents().len() as u64),
+            };
+            children.push(range);
+            i += 1;
+        }
+
+        let with_metadata = has_metadata(&entries);
+        let mut flags = FLAG_HASHES;
+        if with_metadata {
+            flags |= FLAG_METADATA;
+        }
+        let record_len = record_len(flags);
+
+        let mut header = Vec::with_capacity(HEADER_LEN + entries.len() * record_len);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&flags.to_le_bytes());
+        header.extend_from_slice(&(self.entries().len() as u32).to_le_bytes());
+        header.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+
+        let mut body = Vec::new();
+        let body_start = (HEADER_LEN + entries.len() * record_len) as u64;
+
+        for (entry, (first, len)) in entries.iter().zip(children) {
+            let path = entry
+                .path()
+                .to_str()
+                .expect("Embedded paths are always UTF-8");
+            let path_offset = body_start + body.len() as u64;
+            body.extend_from_slice(path.as_bytes());
+
+            let (kind, data_offset, hash) = match entry {
+                DirEntry::Dir(_) => (KIND_DIR, first, 0),
+                DirEntry::File(f) => {
+                    let offset = body_start + body.len() as u64;
+                    body.extend_from_slice(f.contents());
+                    (KIND_FILE, offset, f.hash())
+                }
+            };
+
+            let metadata = entry_metadata(entry);
+
+            header.push(kind);
+            header.push(metadata.is_some() as u8);
+            header.extend_from_slice(&[0; 2]);
+            header.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            header.extend_from_slice(&path_offset.to_le_bytes());
+            header.extend_from_slice(&data_offset.to_le_bytes());
+            header.extend_from_slice(&len.to_le_bytes());
+            header.extend_from_slice(&hash.to_le_bytes());
+
+            if with_metadata {
+                header.extend_from_slice(&metadata.unwrap_or([0; METADATA_LEN]));
+            }
+        }
+
+        header.extend(body);
+        header
+    }
+}
+
+#[cfg(feature = "metadata")]
+fn has_metadata(entries: &[&DirEntry<'_>]) -> bool {
+    entries.iter().any(|e| entry_metadata(e).is_some())
+}
+
+#[cfg(not(feature = "metadata"))]
+fn has_metadata(_entries: &[&DirEntry<'_>]) -> bool {
+    false
+}
+
+#[cfg(feature = "metadata")]
+fn entry_metadata(entry: &DirEntry<'_>) -> Option<[u8; METADATA_LEN]> {
+    fn nanos(time: Option<std::time::SystemTime>) -> Option<u64> {
+        let d = time?
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .ok()?;
+        Some(d.as_nanos() as u64)
+    }
+
+    let m = entry.metadata()?;
+    let mut present = 0;
+    let mut block = [0; METADATA_LEN];
+
+    if let Some(mode) = m.mode() {
+        present |= HAS_MODE;
+        block[4..8].copy_from_slice(&mode.to_le_bytes());
+    }
+    if let (Some(uid), Some(gid)) = (m.uid(), m.gid()) {
+        present |= HAS_OWNER;
+        block[8..12].copy_from_slice(&uid.to_le_bytes());
+        block[12..16].copy_from_slice(&gid.to_le_bytes());
+    }
+
+    let times = [
+        (HAS_ACCESSED, nanos(m.accessed())),
+        (HAS_CREATED, nanos(m.created())),
+        (HAS_MODIFIED, nanos(m.modified())),
+    ];
+    for (i, (bit, time)) in times.iter().enumerate() {
+        if let Some(time) = time {
+            present |= bit;
+            block[16 + i * 8..24 + i * 8].copy_from_slice(&time.to_le_bytes());
+        }
+    }
+
+    block[..4].copy_from_slice(&present.to_le_bytes());
+    Some(block)
+}
+
+#[cfg(not(feature = "metadata"))]
+fn entry_metadata(_entry: &DirEntry<'_>) -> Option<[u8; METADATA_LEN]> {
+    None
+}
+
+fn record_len(flags: u16) -> usize {
+    let mut len = RECORD_LEN;
+    if flags & FLAG_HASHES != 0 {
+        len += 8;
+    }
+    if flags & FLAG_METADATA != 0 {
+        len += METADATA_LEN;
+    }
+    len
+}
+
+/// The 64-bit FNV-1a hash of some bytes.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// A validated archive.
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct Archive<'a> {
+    bytes: &'a [u8],
+    flags: u16,
+    record_len: usize,
+    root_len: u32,
+    count: u32,
+}
+
+impl<'a> Archive<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(ArchiveError::InvalidMagic);
+        }
+
+        let version = read_u16(bytes, 4);
+        if version != VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version));
+        }
+
+        let flags = read_u16(bytes, 6);
+        let archive = Archive {
+            bytes,
+            flags,
+            record_len: record_len(flags),
+            root_len: read_u32(bytes, 8),
+            count: read_u32(bytes, 12),
+        };
+
+        let table_len = (archive.count as usize)
+            .checked_mul(archive.record_len)
+            .and_then(|len| len.checked_add(HEADER_LEN));
+        match table_len {
+            Some(len) if len <= bytes.len() && archive.root_len <= archive.count => {}
+            _ => return Err(ArchiveError::Corrupted),
+        }
+
+        // where the next non-empty directory's children have to start
+        let mut next_child = archive.root_len;
+
+        for index in 0..archive.count {
+            archive.validate(index, &mut next_child)?;
+        }
+
+        if next_child != archive.count {
+            return Err(ArchiveError::Corrupted);
+        }
+
+        Ok(archive)
+    }
+
+    /// Make sure an entry only points at valid data, so the accessors can't
+    /// panic.
+    fn validate(&self, index: u32, next_child: &mut u32) -> Result<(), ArchiveError> {
+        let record = self.record(index);
+
+        let path_len = read_u32(record, 4) as u64;
+        let path_offset = read_u64(record, 8);
+        let path = self
+            .slice(path_offset, path_len)
+            .ok_or(ArchiveError::Corrupted)?;
+        std::str::from_utf8(path).map_err(|_| ArchiveError::Corrupted)?;
+
+        let data = read_u64(record, 16);
+        let len = read_u64(record, 24);
+
+        let valid = match record[0] {
+            KIND_DIR if len == 0 => true,
+            KIND_DIR => {
+                // children always come after their parent, so there can't be
+                // any cycles, and the ranges can't overlap, so walking the
+                // tree visits each entry once
+                let end = data
+                    .checked_add(len)
+                    .filter(|end| *end <= u64::from(self.count));
+
+                match end {
+                    Some(end) if data > u64::from(index) && data == u64::from(*next_child) => {
+                        *next_child = end as u32;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            KIND_FILE => self.slice(data, len).is_some(),
+            _ => false,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(ArchiveError::Corrupted)
+        }
+    }
+
+    fn slice(&self, offset: u64, len: u64) -> Option<&'a [u8]> {
+        let start: usize = offset.try_into().ok()?;
+        let end = start.checked_add(len.try_into().ok()?)?;
+        self.bytes.get(start..end)
+    }
+
+    fn record(&self, index: u32) -> &'a [u8] {
+        let start = HEADER_LEN + index as usize * self.record_len;
+        &self.bytes[start..start + self.record_len]
+    }
+
+    fn entry(&self, index: u32) -> ArchiveEntry<'a> {
+        let record = self.record(index);
+        let path = self
+            .slice(read_u64(record, 8), u64::from(read_u32(record, 4)))
+            .and_then(|p| std::str::from_utf8(p).ok())
+            .expect("validated when the archive was loaded");
+        let data = read_u64(record, 16);
+        let len = read_u64(record, 24);
+
+        if record[0] == KIND_DIR {
+            ArchiveEntry::Dir(ArchiveDir {
+                archive: *self,
+                path,
+                first: data as u32,
+                len: len as u32,
+                #[cfg(feature = "metadata")]
+                metadata: self.metadata(record, 0),
+            })
+        } else {
+            ArchiveEntry::File(ArchiveFile {
+                path,
+                contents: self
+                    .slice(data, len)
+                    .expect("validated when the archive was loaded"),
+                hash: if self.flags & FLAG_HASHES != 0 {
+                    Some(read_u64(record, RECORD_LEN))
+                } else {
+                    None
+                },
+                #[cfg(feature = "metadata")]
+                metadata: self.metadata(record, len),
+            })
+        }
+    }
+
+    #[cfg(feature = "metadata")]
+    fn metadata(&self, record: &[u8], len: u64) -> Option<crate::Metadata> {
+        use std::time::Duration;
+
+        if self.flags & FLAG_METADATA == 0 || record[1] == 0 {
+            return None;
+        }
+
+        let start = if self.flags & FLAG_HASHES != 0 {
+            RECORD_LEN + 8
+        } else {
+            RECORD_LEN
+        };
+        let block = &record[start..start + METADATA_LEN];
+        let present = read_u32(block, 0);
+
+        let mut metadata = crate::Metadata::new().with_len(len);
+
+        if present & HAS_MODE != 0 {
+            metadata = metadata.with_mode(read_u32(block, 4));
+        }
+        if present & HAS_OWNER != 0 {
+            metadata = metadata.with_owner(read_u32(block, 8), read_u32(block, 12));
+        }
+
+        let time = |i: usize| Duration::from_nanos(read_u64(block, 16 + i * 8));
+        if present & HAS_ACCESSED != 0 {
+            metadata = metadata.with_accessed(time(0));
+        }
+        if present & HAS_CREATED != 0 {
+            metadata = metadata.with_created(time(1));
+        }
+        if present & HAS_MODIFIED != 0 {
+            metadata = metadata.with_modified(time(2));
+        }
+
+        Some(metadata)
+    }
+}
+
+impl<'a> fmt::Debug for Archive<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Archive")
+            .field("bytes", &format!("<{} bytes>", self.bytes.len()))
+            .field("flags", &self.flags)
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+/// A directory inside an archive created by [`Dir::to_archive_bytes()`] or
+/// [`crate::include_archive!()`], which borrows its contents from the
+/// archive's bytes.
+///
+/// This has the same API as [`Dir`], but can be loaded at runtime (e.g. from
+/// a plugin).
+///
+/// ```rust
+/// use include_dir::{include_archive, ArchiveDir};
+///
+/// static ARCHIVE: &[u8] = include_archive!("$CARGO_MANIFEST_DIR/src");
+///
+/// let src = ArchiveDir::from_bytes(ARCHIVE).unwrap();
+/// assert!(src.get_file("lib.rs").is_some());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ArchiveDir<'a> {
+    archive: Archive<'a>,
+    path: &'a str,
+    first: u32,
+    len: u32,
+    #[cfg(feature = "metadata")]
+    metadata: Option<crate::Metadata>,
+}
+
+impl<'a> ArchiveDir<'a> {
+    /// Load the root directory of an archive, checking that it is well-formed.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        let archive = Archive::parse(bytes)?;
+
+        Ok(ArchiveDir {
+            archive,
+            path: "",
+            first: 0,
+            len: archive.root_len,
+            #[cfg(feature = "metadata")]
+            metadata: None,
+        })
+    }
+
+    /// The full path for this directory, relative to the root of the archive.
+    pub fn path(&self) -> &'a Path {
+        Path::new(self.path)
+    }
+
+    /// The entries within this directory.
+    pub fn entries(&self) -> impl Iterator<Item = ArchiveEntry<'a>> + 'a {
+        let archive = self.archive;
+        (self.first..self.first + self.len).map(move |i| archive.entry(i))
+    }
+
+    /// Get a list of the files in this directory.
+    pub fn files(&self) -> impl Iterator<Item = ArchiveFile<'a>> + 'a {
+        self.entries().filter_map(ArchiveEntry::into_file)
+    }
+
+    /// Get a list of the sub-directories inside this directory.
+    pub fn dirs(&self) -> impl Iterator<Item = ArchiveDir<'a>> + 'a {
+        self.entries().filter_map(ArchiveEntry::into_dir)
+    }
+
+    /// Recursively iterate over every entry in this directory, visiting each
+    /// directory before its contents.
+    pub fn walk(&self) -> impl Iterator<Item = ArchiveEntry<'a>> + 'a {
+        let mut stack: Vec<ArchiveEntry<'a>> = self.entries().collect();
+        stack.reverse();
+
+        std::iter::from_fn(move || {
+            let entry = stack.pop()?;
+            if let ArchiveEntry::Dir(d) = &entry {
+                let mut children: Vec<_> = d.entries().collect();
+                children.reverse();
+                stack.extend(children);
+            }
+            Some(entry)
+        })
+    }
+
+    /// Search for an entry by its path, normalized the same way as
+    /// [`Dir::get_entry()`].
+    pub fn get_entry<S: AsRef<Path>>(&self, path: S) -> Option<ArchiveEntry<'a>> {
+        crate::paths::lookup(path.as_ref(), |path| {
+            self.get_entry_normalized(Path::new(path))
+        })
+    }
+
+    fn get_entry_normalized(&self, path: &Path) -> Option<ArchiveEntry<'a>> {
+        let mut dir = *self;
+
+        loop {
+            let entry = dir
+                .entries()
+                .find(|e| path == e.path() || path.starts_with(e.path()))?;
+
+            if entry.path() == path {
+                return Some(entry);
+            }
+
+            dir = entry.into_dir()?;
+        }
+    }
+
+    /// Look up a file by name.
+    pub fn get_file<S: AsRef<Path>>(&self, path: S) -> Option<ArchiveFile<'a>> {
+        self.get_entry(path).and_then(ArchiveEntry::into_file)
+    }
+
+    /// Look up a dir by name.
+    pub fn get_dir<S: AsRef<Path>>(&self, path: S) -> Option<ArchiveDir<'a>> {
+        self.get_entry(path).and_then(ArchiveEntry::into_dir)
+    }
+
+    /// Does this directory contain `path`?
+    pub fn contains<S: AsRef<Path>>(&self, path: S) -> bool {
+        self.get_entry(path).is_some()
+    }
+
+    /// Get the directory's [`crate::Metadata`], if available.
+    ///
+    /// The root directory never has any metadata.
+    #[cfg(feature = "metadata")]
+    pub fn metadata(&self) -> Option<&crate::Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Search for a file or directory with a glob pattern.
+    #[cfg(feature = "glob")]
+    pub fn find(
+        &self,
+        glob: &str,
+    ) -> Result<impl Iterator<Item = ArchiveEntry<'a>> + 'a, glob::PatternError> {
+        let pattern = glob::Pattern::new(glob)?;
+
+        Ok(self
+            .walk()
+            .filter(move |entry| pattern.matches_path(entry.path())))
+    }
+}
+
+/// A file inside an archive.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct ArchiveFile<'a> {
+    path: &'a str,
+    contents: &'a [u8],
+    hash: Option<u64>,
+    #[cfg(feature = "metadata")]
+    metadata: Option<crate::Metadata>,
+}
+
+impl<'a> ArchiveFile<'a> {
+    /// The full path for this file, relative to the root of the archive.
+    pub fn path(&self) -> &'a Path {
+        Path::new(self.path)
+    }
+
+    /// The file's raw contents.
+    pub fn contents(&self) -> &'a [u8] {
+        self.contents
+    }
+
+    /// The file's contents interpreted as a string.
+    pub fn contents_utf8(&self) -> Option<&'a str> {
+        std::str::from_utf8(self.contents).ok()
+    }
+
+    /// The 64-bit FNV-1a hash of the file's contents, if the archive
+    /// contains hashes.
+    pub fn hash(&self) -> Option<u64> {
+        self.hash
+    }
+
+    /// Does the file's contents still match the hash stored in the archive?
+    ///
+    /// Files without a hash are always considered valid.
+    pub fn verify(&self) -> bool {
+        self.hash.map_or(true, |hash| hash == fnv1a(self.contents))
+    }
+
+    /// Get the file's [`crate::Metadata`], if available.
+    #[cfg(feature = "metadata")]
+    pub fn metadata(&self) -> Option<&crate::Metadata> {
+        self.metadata.as_ref()
+    }
+}
+
+impl<'a> fmt::Debug for ArchiveFile<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("ArchiveFile");
+
+        d.field("path", &self.path)
+            .field("contents", &format!("<{} bytes>", self.contents.len()))
+            .field("hash", &self.hash);
+
+        #[cfg(feature = "metadata")]
+        d.field("metadata", &self.metadata);
+
+        d.finish()
+    }
+}
+
+/// An entry in an [`ArchiveDir`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ArchiveEntry<'a> {
+    /// A directory.
+    Dir(ArchiveDir<'a>),
+    /// A file.
+    File(ArchiveFile<'a>),
+}
+
+impl<'a> ArchiveEntry<'a> {
+    /// The entry's full path.
+    pub fn path(&self) -> &'a Path {
+        match self {
+            ArchiveEntry::Dir(d) => d.path(),
+            ArchiveEntry::File(f) => f.path(),
+        }
+    }
+
+    /// Try to get this as an [`ArchiveDir`], if it is one.
+    pub fn into_dir(self) -> Option<ArchiveDir<'a>> {
+        match self {
+            ArchiveEntry::Dir(d) => Some(d),
+            ArchiveEntry::File(_) => None,
+        }
+    }
+
+    /// Try to get this as an [`ArchiveFile`], if it is one.
+    pub fn into_file(self) -> Option<ArchiveFile<'a>> {
+        match self {
+            ArchiveEntry::File(f) => Some(f),
+            ArchiveEntry::Dir(_) => None,
+        }
+    }
+
+    /// Get the entry's [`crate::Metadata`], if available.
+    #[cfg(feature = "metadata")]
+    pub fn metadata(&self) -> Option<&crate::Metadata> {
+        match self {
+            ArchiveEntry::Dir(d) => d.metadata(),
+            ArchiveEntry::File(f) => f.metadata(),
+        }
+    }
+}
+
+/// The error returned when [`ArchiveDir::from_bytes()`] is given bytes that
+/// aren't a valid archive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArchiveError {
+    /// The bytes don't start with the archive's magic number.
+    InvalidMagic,
+    /// The archive was created by an incompatible version of this crate.
+    UnsupportedVersion(u16),
+    /// The archive is truncated or contains invalid offsets.
+    Corrupted,
+}
+
+impl Display for ArchiveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::InvalidMagic => write!(f, "Not an include_dir archive"),
+            ArchiveError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported archive version, {}", v)
+            }
+            ArchiveError::Corrupted => write!(f, "The archive is corrupted"),
+        }
+    }
+}
+
+impl Error for ArchiveError {}
@@ -0,0 +1,248 @@
+use crate::{
+    overlay, ArchiveDir, ArchiveEntry, Dir, DirBuf, DirEntryBuf, Overlay, OverlayEntry, OverlayFile,
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Something files can be read from, like an embedded [`Dir`], an owned
+/// [`DirBuf`], a directory on disk or an [`Overlay`].
+///
+/// Libraries can accept `impl AssetSource` instead of a concrete
+/// `&'static Dir<'static>`, letting callers pass in generated fixtures or
+/// assets which are only known at runtime.
+///
+/// Paths are normalized the same way as [`Dir::get_entry()`] and are always
+/// relative to the root of the source.
+///
+/// ```rust
+/// use include_dir::{include_dir, AssetSource, Dir, DirBuf};
+/// use std::path::Path;
+///
+/// fn stylesheet(assets: impl AssetSource) -> Option<String> {
+///     let file = assets.get_file(Path::new("css/site.css"))?;
+///     file.contents_utf8().map(String::from)
+/// }
+///
+/// static ASSETS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests");
+/// assert_eq!(stylesheet(&ASSETS), None);
+///
+/// let mut fixture = DirBuf::new();
+/// fixture.insert_file("css/site.css", "h1 { color: red; }");
+/// assert_eq!(stylesheet(&fixture).as_deref(), Some("h1 { color: red; }"));
+/// ```
+pub trait AssetSource {
+    /// Look up a file by name.
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>>;
+
+    /// The entries at the top level of the source, sorted by path.
+    fn entries(&self) -> Vec<OverlayEntry>;
+
+    /// Recursively iterate over every entry in the source, sorted by path.
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_>;
+
+    /// Does the source contain `path`?
+    fn contains(&self, path: &Path) -> bool {
+        crate::paths::lookup(path, |path| {
+            self.walk().find(|e| e.path() == Path::new(path)).map(drop)
+        })
+        .is_some()
+    }
+}
+
+/// Paths are relative to the [`Dir`] itself, the same as when it is used as
+/// a layer in an [`Overlay`], so a sub-directory can be passed around as its
+/// own source.
+impl<'a> AssetSource for Dir<'a> {
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+        let path = crate::paths::normalize(path)?;
+        let file = overlay::get_embedded(self, &path)?.as_file()?;
+
+        Some(OverlayFile::new(
+            PathBuf::from(path),
+            Cow::Borrowed(file.contents()),
+        ))
+    }
+
+    fn entries(&self) -> Vec<OverlayEntry> {
+        Dir::entries(self)
+            .iter()
+            .map(|e| overlay::relative_entry(self, e))
+            .collect()
+    }
+
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+        Box::new(Dir::walk(self).map(move |e| overlay::relative_entry(self, e)))
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        match crate::paths::normalize(path) {
+            Some(path) => overlay::get_embedded(self, &path).is_some(),
+            None => false,
+        }
+    }
+}
+
+impl AssetSource for DirBuf {
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+        let file = DirBuf::get_file(self, path)?;
+
+        Some(OverlayFile::new(
+            file.path().to_path_buf(),
+            Cow::Borrowed(file.contents()),
+        ))
+    }
+
+    fn entries(&self) -> Vec<OverlayEntry> {
+        DirBuf::entries(self)
+            .iter()
+            .map(OverlayEntry::from)
+            .collect()
+    }
+
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+        Box::new(DirBuf::walk(self).map(OverlayEntry::from))
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        DirBuf::contains(self, path)
+    }
+}
+
+impl<'a> AssetSource for ArchiveDir<'a> {
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+        let file = ArchiveDir::get_file(self, path)?;
+
+        Some(OverlayFile::new(
+            file.path().to_path_buf(),
+            Cow::Borrowed(file.contents()),
+        ))
+    }
+
+    fn entries(&self) -> Vec<OverlayEntry> {
+        ArchiveDir::entries(self).map(OverlayEntry::from).collect()
+    }
+
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+        Box::new(ArchiveDir::walk(self).map(OverlayEntry::from))
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        ArchiveDir::contains(self, path)
+    }
+}
+
+/// Files that can't be read are treated as missing. Use the inherent
+/// [`Overlay::get_file()`] to see why.
+impl<'a> AssetSource for Overlay<'a> {
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+        Overlay::get_file(self, path).ok().flatten()
+    }
+
+    fn entries(&self) -> Vec<OverlayEntry> {
+        Overlay::entries(self)
+    }
+
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+        Box::new(Overlay::walk(self))
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        Overlay::contains(self, path).unwrap_or(false)
+    }
+}
+
+/// A directory on the real filesystem.
+///
+/// Files that can't be read are treated as missing.
+impl AssetSource for Path {
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+        let path = crate::paths::normalize(path)?;
+        overlay::read_file(self, &path).ok().flatten()
+    }
+
+    fn entries(&self) -> Vec<OverlayEntry> {
+        let mut entries = BTreeMap::new();
+        overlay::read_dir(self, self, false, &mut entries);
+        entries.into_values().collect()
+    }
+
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+        let mut entries = BTreeMap::new();
+        overlay::read_dir(self, self, true, &mut entries);
+        Box::new(entries.into_values())
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        match crate::paths::normalize(path) {
+            Some(path) => self.join(path).exists(),
+            None => false,
+        }
+    }
+}
+
+/// A directory on the real filesystem.
+impl AssetSource for PathBuf {
+    fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+        self.as_path().get_file(path)
+    }
+
+    fn entries(&self) -> Vec<OverlayEntry> {
+        self.as_path().entries()
+    }
+
+    fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+        self.as_path().walk()
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.as_path().contains(path)
+    }
+}
+
+macro_rules! forward_impl {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<T: AssetSource + ?Sized> AssetSource for $ty {
+                fn get_file(&self, path: &Path) -> Option<OverlayFile<'_>> {
+                    (**self).get_file(path)
+                }
+
+                fn entries(&self) -> Vec<OverlayEntry> {
+                    (**self).entries()
+                }
+
+                fn walk(&self) -> Box<dyn Iterator<Item = OverlayEntry> + '_> {
+                    (**self).walk()
+                }
+
+                fn contains(&self, path: &Path) -> bool {
+                    (**self).contains(path)
+                }
+            }
+        )*
+    };
+}
+
+forward_impl!(&T, &mut T, Box<T>, Arc<T>);
+
+impl From<&DirEntryBuf> for OverlayEntry {
+    fn from(entry: &DirEntryBuf) -> Self {
+        match entry {
+            DirEntryBuf::Dir(d) => OverlayEntry::Dir(d.path().to_path_buf()),
+            DirEntryBuf::File(f) => OverlayEntry::File(f.path().to_path_buf()),
+        }
+    }
+}
+
+impl<'a> From<ArchiveEntry<'a>> for OverlayEntry {
+    fn from(entry: ArchiveEntry<'a>) -> Self {
+        match entry {
+            ArchiveEntry::Dir(d) => OverlayEntry::Dir(d.path().to_path_buf()),
+            ArchiveEntry::File(f) => OverlayEntry::File(f.path().to_path_buf()),
+        }
+    }
+}
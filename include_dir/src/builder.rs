@@ -0,0 +1,246 @@
+use glob::Pattern;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// Preprocess a directory from a build script (e.g. minifying CSS or
+/// converting Markdown to HTML) so the result can be embedded using
+/// [`crate::include_dir!()`].
+///
+/// Procedural macros can't run your code, so instead the [`Builder`] copies
+/// a directory into `$OUT_DIR`, passing each file through the transforms
+/// whose glob pattern matches it. Patterns are matched against each file's
+/// path relative to the source directory, and transforms run in the order
+/// they were added, so a rule added after a [`Builder::rename()`] sees the
+/// new path.
+///
+/// ```rust,no_run
+/// // build.rs
+/// use include_dir::Builder;
+/// use std::{env, path::Path};
+///
+/// fn main() -> std::io::Result<()> {
+///     let out_dir = env::var("OUT_DIR").unwrap();
+///
+///     Builder::new("assets")
+///         .transform("**/*.css", |_path, css| minify(css))
+///         .transform("**/*.md", |_path, markdown| to_html(markdown))
+///         .rename("**/*.md", |path| path.with_extension("html"))
+///         .build(Path::new(&out_dir).join("assets"))
+/// }
+/// # fn minify(css: Vec<u8>) -> Vec<u8> { css }
+/// # fn to_html(md: Vec<u8>) -> Vec<u8> { md }
+/// ```
+///
+/// Then in your crate:
+///
+/// ```rust,ignore
+/// static ASSETS: Dir<'_> = include_dir!("$OUT_DIR/assets");
+/// ```
+pub struct Builder {
+    source: PathBuf,
+    rules: Vec<(Pattern, Rule)>,
+}
+
+type TransformFn = dyn Fn(&Path, Vec<u8>) -> Vec<u8>;
+
+enum Rule {
+    Transform(Box<TransformFn>),
+    Rename(Box<dyn Fn(&Path) -> PathBuf>),
+}
+
+impl Builder {
+    /// Create a [`Builder`] which will process the files in `source`.
+    pub fn new(source: impl Into<PathBuf>) -> Self {
+        Builder {
+            source: source.into(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Pass the contents of every file matching `glob` through a function.
+    ///
+    /// The function is given the file's (relative) path and its contents,
+    /// and returns the new contents.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `glob` isn't a valid glob pattern.
+    pub fn transform<F>(self, glob: &str, transform: F) -> Self
+    where
+        F: Fn(&Path, Vec<u8>) -> Vec<u8> + 'static,
+    {
+        self.rule(glob, Rule::Transform(Box::new(transform)))
+    }
+
+    /// Change the (relative) path of every file matching `glob`, e.g. to
+    /// give Markdown files converted to HTML a `.html` extension.
+    ///
+    /// [`Builder::build()`] fails with [`io::ErrorKind::InvalidInput`] if the
+    /// new path is empty, absolute or contains `..`, because it would end up
+    /// outside the destination directory.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `glob` isn't a valid glob pattern.
+    pub fn rename<F>(self, glob: &str, rename: F) -> Self
+    where
+        F: Fn(&Path) -> PathBuf + 'static,
+    {
+        self.rule(glob, Rule::Rename(Box::new(rename)))
+    }
+
+    fn rule(mut self, glob: &str, rule: Rule) -> Self {
+        let pattern = Pattern::new(glob)
+            .unwrap_or_else(|e| panic!("\"{}\" isn't a valid glob pattern: {}", glob, e));
+        self.rules.push((pattern, rule));
+        self
+    }
+
+    /// Process every file and write the results to `dest`.
+    ///
+    /// Anything already in `dest` is removed first, so files deleted from
+    /// the source directory don't linger. To make sure that can't delete the
+    /// source directory itself, it is an [`io::ErrorKind::InvalidInput`]
+    /// error for `dest` to be or contain the source directory. This also
+    /// tells cargo to re-run the build script when the source directory
+    /// changes.
+    pub fn build(&self, dest: impl AsRef<Path>) -> io::Result<()> {
+        let dest = dest.as_ref();
+        println!("cargo:rerun-if-changed={}", self.source.display());
+
+        if dest.exists()
+            && self
+                .source
+                .canonicalize()?
+                .starts_with(dest.canonicalize()?)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Building into \"{}\" would delete the source directory, \"{}\"",
+                    dest.display(),
+                    self.source.display()
+                ),
+            ));
+        }
+
+        let mut outputs: BTreeMap<PathBuf, (PathBuf, Vec<u8>)> = BTreeMap::new();
+
+        for original in files(&self.source)? {
+            let contents = fs::read(self.source.join(&original))?;
+            let (path, contents) = self.process(original.clone(), contents)?;
+
+            if let Some((existing, _)) = outputs.get(&path) {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "\"{}\" is produced by both \"{}\" and \"{}\"",
+                        path.display(),
+                        existing.display(),
+                        original.display()
+                    ),
+                ));
+            }
+
+            outputs.insert(path, (original, contents));
+        }
+
+        if dest.exists() {
+            fs::remove_dir_all(dest)?;
+        }
+        fs::create_dir_all(dest)?;
+
+        for (path, (_, contents)) in outputs {
+            let path = dest.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+
+        Ok(())
+    }
+
+    fn process(&self, mut path: PathBuf, mut contents: Vec<u8>) -> io::Result<(PathBuf, Vec<u8>)> {
+        for (pattern, rule) in &self.rules {
+            if !pattern.matches_path(&path) {
+                continue;
+            }
+
+            match rule {
+                Rule::Transform(transform) => contents = transform(&path, contents),
+                Rule::Rename(rename) => {
+                    let renamed = rename(&path);
+                    check_relative(&path, &renamed)?;
+                    path = renamed;
+                }
+            }
+        }
+
+        Ok((path, contents))
+    }
+}
+
+impl Debug for Builder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rules: Vec<_> = self
+            .rules
+            .iter()
+            .map(|(pattern, rule)| match rule {
+                Rule::Transform(_) => format!("transform({})", pattern),
+                Rule::Rename(_) => format!("rename({})", pattern),
+            })
+            .collect();
+
+        f.debug_struct("Builder")
+            .field("source", &self.source)
+            .field("rules", &rules)
+            .finish()
+    }
+}
+
+/// Make sure a renamed file stays inside the destination directory.
+fn check_relative(original: &Path, renamed: &Path) -> io::Result<()> {
+    let mut components = renamed.components().peekable();
+    let is_relative = components.peek().is_some()
+        && components.all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+
+    if is_relative {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "\"{}\" was renamed to \"{}\", which is outside the destination directory",
+                original.display(),
+                renamed.display()
+            ),
+        ))
+    }
+}
+
+/// Every file inside `root`, relative to `root`.
+fn files(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut stack = vec![PathBuf::new()];
+
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(root.join(&dir))? {
+            let path = dir.join(entry?.file_name());
+
+            // follow symlinks, the same as include_dir!()
+            if fs::metadata(root.join(&path))?.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
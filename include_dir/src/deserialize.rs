@@ -0,0 +1,170 @@
+use crate::File;
+use serde::Deserialize;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+impl<'a> File<'a> {
+    /// Deserialize the file's contents as JSON.
+    #[cfg(feature = "json")]
+    pub fn parse_json<T: Deserialize<'a>>(&self) -> Result<T, ParseError> {
+        serde_json::from_slice(self.contents())
+            .map_err(|e| ParseError::new(self.path(), ParseErrorKind::Json(e)))
+    }
+
+    /// Deserialize the file's contents as TOML.
+    #[cfg(feature = "toml")]
+    pub fn parse_toml<T: Deserialize<'a>>(&self) -> Result<T, ParseError> {
+        let text = std::str::from_utf8(self.contents())
+            .map_err(|e| ParseError::new(self.path(), ParseErrorKind::Utf8(e)))?;
+
+        toml::from_str(text).map_err(|e| ParseError::new(self.path(), ParseErrorKind::Toml(e)))
+    }
+
+    /// Deserialize the file's contents as YAML.
+    #[cfg(feature = "yaml")]
+    pub fn parse_yaml<T: Deserialize<'a>>(&self) -> Result<T, ParseError> {
+        serde_yaml::from_slice(self.contents())
+            .map_err(|e| ParseError::new(self.path(), ParseErrorKind::Yaml(e)))
+    }
+
+    /// Deserialize the file's contents, using its extension to figure out
+    /// which format it is in.
+    ///
+    /// Only formats whose feature flag (`json`, `toml` or `yaml`) is enabled
+    /// are recognised.
+    pub fn parse<T: Deserialize<'a>>(&self) -> Result<T, ParseError> {
+        let extension = self.path().extension().and_then(|ext| ext.to_str());
+
+        match extension {
+            #[cfg(feature = "json")]
+            Some("json") => self.parse_json(),
+            #[cfg(feature = "toml")]
+            Some("toml") => self.parse_toml(),
+            #[cfg(feature = "yaml")]
+            Some("yaml") | Some("yml") => self.parse_yaml(),
+            _ => Err(ParseError::new(
+                self.path(),
+                ParseErrorKind::UnsupportedFormat,
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "glob")]
+impl<'a> crate::Dir<'a> {
+    /// Deserialize every file matching a glob pattern, using
+    /// [`File::parse()`] to pick a format based on each file's extension.
+    ///
+    /// This needs the `glob` feature, as well as the `json`, `toml` or `yaml`
+    /// feature for each format being loaded.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "toml")] {
+    /// use include_dir::{include_dir, Dir};
+    /// use std::{collections::BTreeMap, path::Path};
+    ///
+    /// static PROJECT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR");
+    ///
+    /// let manifests: BTreeMap<_, toml::Table> = PROJECT_DIR.load_all("**/*.toml").unwrap();
+    /// assert!(manifests[Path::new("Cargo.toml")].contains_key("package"));
+    /// # }
+    /// ```
+    pub fn load_all<T: Deserialize<'a>>(
+        &self,
+        glob: &str,
+    ) -> Result<std::collections::BTreeMap<&'a Path, T>, ParseError> {
+        let files = self
+            .find(glob)
+            .map_err(|e| ParseError::new(glob, ParseErrorKind::Pattern(e)))?
+            .filter_map(crate::DirEntry::as_file);
+
+        files.map(|f| Ok((f.path(), f.parse()?))).collect()
+    }
+}
+
+/// The error returned when an embedded file can't be deserialized.
+#[derive(Debug)]
+pub struct ParseError {
+    path: PathBuf,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    fn new(path: impl Into<PathBuf>, kind: ParseErrorKind) -> Self {
+        ParseError {
+            path: path.into(),
+            kind,
+        }
+    }
+
+    /// The path of the file that couldn't be deserialized.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What went wrong.
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Unable to parse \"{}\": ", self.path.display())?;
+
+        match &self.kind {
+            ParseErrorKind::Utf8(e) => write!(f, "{}", e),
+            #[cfg(feature = "json")]
+            ParseErrorKind::Json(e) => write!(f, "{}", e),
+            #[cfg(feature = "toml")]
+            ParseErrorKind::Toml(e) => write!(f, "{}", e),
+            #[cfg(feature = "yaml")]
+            ParseErrorKind::Yaml(e) => write!(f, "{}", e),
+            #[cfg(feature = "glob")]
+            ParseErrorKind::Pattern(e) => write!(f, "{}", e),
+            ParseErrorKind::UnsupportedFormat => write!(f, "unsupported format"),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ParseErrorKind::Utf8(e) => Some(e),
+            #[cfg(feature = "json")]
+            ParseErrorKind::Json(e) => Some(e),
+            #[cfg(feature = "toml")]
+            ParseErrorKind::Toml(e) => Some(e),
+            #[cfg(feature = "yaml")]
+            ParseErrorKind::Yaml(e) => Some(e),
+            #[cfg(feature = "glob")]
+            ParseErrorKind::Pattern(e) => Some(e),
+            ParseErrorKind::UnsupportedFormat => None,
+        }
+    }
+}
+
+/// The reasons a [`ParseError`] may occur.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The file isn't valid UTF-8.
+    Utf8(std::str::Utf8Error),
+    /// The file isn't valid JSON.
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    /// The file isn't valid TOML.
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    /// The file isn't valid YAML.
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    /// The glob pattern passed to [`crate::Dir::load_all()`] was invalid.
+    #[cfg(feature = "glob")]
+    Pattern(glob::PatternError),
+    /// The file's extension doesn't correspond to a known format.
+    UnsupportedFormat,
+}
@@ -1,18 +1,51 @@
-use crate::{file::File, DirEntry};
+use crate::{file::File, DirEntry, Manifest};
 use std::fs;
 use std::path::Path;
 
 /// A directory.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Dir<'a> {
     path: &'a str,
     entries: &'a [DirEntry<'a>],
+    /// The number of files and their total size, when computed ahead of time
+    /// by [`crate::include_dir!()`].
+    stats: Option<Stats>,
+    #[cfg(feature = "metadata")]
+    metadata: Option<crate::Metadata>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Stats {
+    file_count: usize,
+    total_size: u64,
 }
 
 impl<'a> Dir<'a> {
     /// Create a new [`Dir`].
     pub const fn new(path: &'a str, entries: &'a [DirEntry<'a>]) -> Self {
-        Dir { path, entries }
+        Dir {
+            path,
+            entries,
+            stats: None,
+            #[cfg(feature = "metadata")]
+            metadata: None,
+        }
+    }
+
+    /// Record the number of files and their total size so they don't need to
+    /// be computed at runtime.
+    ///
+    /// This is used by the code [`crate::include_dir!()`] generates, and the
+    /// values aren't checked.
+    #[doc(hidden)]
+    pub const fn with_stats(self, file_count: usize, total_size: u64) -> Self {
+        Dir {
+            stats: Some(Stats {
+                file_count,
+                total_size,
+            }),
+            ..self
+        }
     }
 
     /// The full path for this [`Dir`], relative to the directory passed to
@@ -26,6 +59,58 @@ impl<'a> Dir<'a> {
         self.entries
     }
 
+    /// The number of files in this directory and all of its sub-directories.
+    ///
+    /// This is computed by [`crate::include_dir!()`] at compile time, so
+    /// it doesn't need to walk the tree.
+    pub const fn file_count(&self) -> usize {
+        if let Some(stats) = self.stats {
+            return stats.file_count;
+        }
+
+        let mut count = 0;
+        let mut i = 0;
+
+        while i < self.entries.len() {
+            count += match &self.entries[i] {
+                DirEntry::Dir(d) => d.file_count(),
+                DirEntry::File(_) => 1,
+            };
+            i += 1;
+        }
+
+        count
+    }
+
+    /// The combined size of every file in this directory and all of its
+    /// sub-directories, in bytes.
+    ///
+    /// Like [`Dir::file_count()`], this is computed at compile time.
+    pub const fn total_size(&self) -> u64 {
+        if let Some(stats) = self.stats {
+            return stats.total_size;
+        }
+
+        let mut size = 0;
+        let mut i = 0;
+
+        while i < self.entries.len() {
+            size += match &self.entries[i] {
+                DirEntry::Dir(d) => d.total_size(),
+                DirEntry::File(f) => f.contents().len() as u64,
+            };
+            i += 1;
+        }
+
+        size
+    }
+
+    /// List every file in this directory and its sub-directories along with
+    /// its size, hash and MIME type, e.g. to serve as an asset manifest.
+    pub fn manifest(&self) -> Manifest<'a> {
+        Manifest::new(self)
+    }
+
     /// Get a list of the files in this directory.
     pub fn files(&self) -> impl Iterator<Item = &'a File<'a>> + 'a {
         self.entries().iter().filter_map(DirEntry::as_file)
@@ -36,8 +121,30 @@ impl<'a> Dir<'a> {
         self.entries().iter().filter_map(DirEntry::as_dir)
     }
 
+    /// Recursively iterate over every [`DirEntry`] in this directory, visiting
+    /// each directory before its contents.
+    pub fn walk(&self) -> impl Iterator<Item = &'a DirEntry<'a>> + 'a {
+        let mut stack: Vec<&'a DirEntry<'a>> = self.entries().iter().rev().collect();
+
+        std::iter::from_fn(move || {
+            let entry = stack.pop()?;
+            stack.extend(entry.children().iter().rev());
+            Some(entry)
+        })
+    }
+
     /// Recursively search for a [`DirEntry`] with a particular path.
+    ///
+    /// The path is normalized before searching, so `./index.html`,
+    /// `/index.html` and `css\site.css` will all be found. Any `.` and `..`
+    /// components are resolved without escaping the root directory. Use
+    /// [`Dir::get_entry_strict()`] if you want an exact match instead.
     pub fn get_entry<S: AsRef<Path>>(&self, path: S) -> Option<&'a DirEntry<'a>> {
+        crate::paths::lookup(path.as_ref(), |path| self.get_entry_strict(path))
+    }
+
+    /// Recursively search for a [`DirEntry`] whose path is exactly `path`.
+    pub fn get_entry_strict<S: AsRef<Path>>(&self, path: S) -> Option<&'a DirEntry<'a>> {
         let path = path.as_ref();
 
         for entry in self.entries() {
@@ -46,7 +153,7 @@ impl<'a> Dir<'a> {
             }
 
             if let DirEntry::Dir(d) = entry {
-                if let Some(nested) = d.get_entry(path) {
+                if let Some(nested) = d.get_entry_strict(path) {
                     return Some(nested);
                 }
             }
@@ -55,16 +162,121 @@ impl<'a> Dir<'a> {
         None
     }
 
+    /// Recursively search for a [`DirEntry`], ignoring differences in case.
+    ///
+    /// The path is normalized the same way as [`Dir::get_entry()`] and
+    /// compared using the Unicode lowercase mapping of each path. If several
+    /// entries only differ by case, the first one (in sorted order) is
+    /// returned.
+    pub fn get_entry_ignore_case<S: AsRef<Path>>(&self, path: S) -> Option<&'a DirEntry<'a>> {
+        crate::paths::lookup(path.as_ref(), |path| {
+            self.get_entry_folded(&crate::paths::fold_case(path))
+        })
+    }
+
+    fn get_entry_folded(&self, folded: &str) -> Option<&'a DirEntry<'a>> {
+        for entry in self.entries() {
+            let entry_path = crate::paths::fold_case(&entry.path().to_string_lossy());
+
+            if entry_path == folded {
+                return Some(entry);
+            }
+
+            if let DirEntry::Dir(d) = entry {
+                let is_parent = folded
+                    .strip_prefix(entry_path.as_str())
+                    .map_or(false, |rest| rest.starts_with('/'));
+
+                if is_parent {
+                    if let Some(nested) = d.get_entry_folded(folded) {
+                        return Some(nested);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
     /// Look up a file by name.
     pub fn get_file<S: AsRef<Path>>(&self, path: S) -> Option<&'a File<'a>> {
         self.get_entry(path).and_then(DirEntry::as_file)
     }
 
+    /// Look up a file by its exact path, without any normalization.
+    pub fn get_file_strict<S: AsRef<Path>>(&self, path: S) -> Option<&'a File<'a>> {
+        self.get_entry_strict(path).and_then(DirEntry::as_file)
+    }
+
+    /// Look up a file by name, ignoring differences in case.
+    pub fn get_file_ignore_case<S: AsRef<Path>>(&self, path: S) -> Option<&'a File<'a>> {
+        self.get_entry_ignore_case(path).and_then(DirEntry::as_file)
+    }
+
+    /// Look up a file using the fingerprinted path it was given by
+    /// `include_dir!(..., fingerprint = true)`, e.g. `js/app.3f9a1c0d.js`.
+    ///
+    /// Like [`Dir::get_file()`], the path is normalized first.
+    pub fn get_by_fingerprinted_path<S: AsRef<Path>>(&self, path: S) -> Option<&'a File<'a>> {
+        crate::paths::lookup(path.as_ref(), |path| {
+            let path = Path::new(path);
+
+            // the fingerprint only changes the file name, so the file must
+            // be in the same directory
+            let mut siblings = match path.parent() {
+                Some(parent) if parent != Path::new("") => self.get_dir_strict(parent)?.files(),
+                _ => self.files(),
+            };
+
+            siblings.find(|f| f.fingerprinted_path() == Some(path))
+        })
+    }
+
+    /// The URL to serve a file at, relative to the root of the embedded
+    /// directory.
+    ///
+    /// This is the file's fingerprinted path when it was embedded using
+    /// `fingerprint = true` and its normal path otherwise, so templates can
+    /// refer to `app.js` while browsers are given `/app.3f9a1c0d.js`.
+    /// Returns `None` if there is no such file.
+    ///
+    /// ```rust
+    /// use include_dir::{include_dir, Dir};
+    ///
+    /// static ASSETS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures", fingerprint = true);
+    ///
+    /// let url = ASSETS.asset_url("data/server.json").unwrap();
+    /// assert!(url.starts_with("/data/server."));
+    /// assert!(ASSETS.get_by_fingerprinted_path(&url).is_some());
+    /// ```
+    pub fn asset_url<S: AsRef<Path>>(&self, path: S) -> Option<String> {
+        let file = self.get_file(path)?;
+        let path = file.fingerprinted_path().unwrap_or_else(|| file.path());
+
+        let mut url = String::new();
+        for component in path.iter() {
+            url.push('/');
+            url.push_str(&component.to_string_lossy());
+        }
+
+        Some(url)
+    }
+
     /// Look up a dir by name.
     pub fn get_dir<S: AsRef<Path>>(&self, path: S) -> Option<&'a Dir<'a>> {
         self.get_entry(path).and_then(DirEntry::as_dir)
     }
 
+    /// Look up a dir by its exact path, without any normalization.
+    pub fn get_dir_strict<S: AsRef<Path>>(&self, path: S) -> Option<&'a Dir<'a>> {
+        self.get_entry_strict(path).and_then(DirEntry::as_dir)
+    }
+
+    /// Look up a dir by name, ignoring differences in case.
+    pub fn get_dir_ignore_case<S: AsRef<Path>>(&self, path: S) -> Option<&'a Dir<'a>> {
+        self.get_entry_ignore_case(path).and_then(DirEntry::as_dir)
+    }
+
     /// Does this directory contain `path`?
     pub fn contains<S: AsRef<Path>>(&self, path: S) -> bool {
         self.get_entry(path).is_some()
@@ -94,3 +306,43 @@ impl<'a> Dir<'a> {
         Ok(())
     }
 }
+
+#[cfg(feature = "metadata")]
+impl<'a> Dir<'a> {
+    /// Set the [`Metadata`] associated with a [`Dir`].
+    ///
+    /// [`Metadata`]: crate::Metadata
+    pub const fn with_metadata(self, metadata: crate::Metadata) -> Self {
+        Dir {
+            metadata: Some(metadata),
+            ..self
+        }
+    }
+
+    /// Get the [`Dir`]'s [`Metadata`], if available.
+    ///
+    /// [`Metadata`]: crate::Metadata
+    pub fn metadata(&self) -> Option<&crate::Metadata> {
+        self.metadata.as_ref()
+    }
+}
+
+// the precomputed stats are a cache, so they shouldn't affect equality
+impl PartialEq for Dir<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let Dir {
+            path,
+            entries,
+            stats: _,
+            #[cfg(feature = "metadata")]
+            metadata,
+        } = self;
+
+        #[cfg(feature = "metadata")]
+        if *metadata != other.metadata {
+            return false;
+        }
+
+        *path == other.path && *entries == other.entries
+    }
+}
@@ -0,0 +1,435 @@
+use crate::{ArchiveDir, ArchiveEntry, Dir, DirEntry, File};
+use std::{fs, io, path::Path};
+
+/// An owned directory tree, for when the files aren't known at compile time
+/// (e.g. a bundle that was downloaded or read from disk).
+///
+/// A [`DirBuf`] can also be created from an embedded [`Dir`] or an
+/// [`ArchiveDir`] using [`From`], so embedded defaults and user-supplied
+/// bundles can share the same code paths.
+///
+/// This is the owned counterpart to [`Dir`], and paths follow the same rules:
+/// every entry's path is relative to the root of the tree and uses `/` as a
+/// separator.
+///
+/// ```rust
+/// use include_dir::DirBuf;
+///
+/// let mut bundle = DirBuf::new();
+/// bundle.insert_file("index.html", "<h1>Hello, World!</h1>");
+/// bundle.insert_file("css/site.css", "h1 { color: red; }");
+///
+/// assert!(bundle.get_dir("css").is_some());
+/// assert_eq!(
+///     bundle.get_file("css/site.css").unwrap().contents_utf8(),
+///     Some("h1 { color: red; }")
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirBuf {
+    path: String,
+    entries: Vec<DirEntryBuf>,
+}
+
+impl DirBuf {
+    /// Create an empty [`DirBuf`].
+    pub fn new() -> Self {
+        DirBuf::default()
+    }
+
+    /// Recursively read a directory from disk.
+    ///
+    /// Entries which aren't files or directories (e.g. sockets) are skipped,
+    /// and it is an error for a path to not be valid UTF-8.
+    pub fn from_fs<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let root = path.as_ref();
+        read_dir(root, root)
+    }
+
+    /// The full path for this [`DirBuf`], relative to the root of the tree.
+    pub fn path(&self) -> &Path {
+        Path::new(&self.path)
+    }
+
+    /// The entries within this [`DirBuf`], sorted by name.
+    pub fn entries(&self) -> &[DirEntryBuf] {
+        &self.entries
+    }
+
+    /// Get a list of the files in this directory.
+    pub fn files(&self) -> impl Iterator<Item = &FileBuf> {
+        self.entries.iter().filter_map(DirEntryBuf::as_file)
+    }
+
+    /// Get a list of the sub-directories inside this directory.
+    pub fn dirs(&self) -> impl Iterator<Item = &DirBuf> {
+        self.entries.iter().filter_map(DirEntryBuf::as_dir)
+    }
+
+    /// Recursively iterate over every [`DirEntryBuf`] in this directory,
+    /// visiting each directory before its contents.
+    pub fn walk(&self) -> impl Iterator<Item = &DirEntryBuf> {
+        let mut stack: Vec<&DirEntryBuf> = self.entries.iter().rev().collect();
+
+        std::iter::from_fn(move || {
+            let entry = stack.pop()?;
+            stack.extend(entry.children().iter().rev());
+            Some(entry)
+        })
+    }
+
+    /// Recursively search for a [`DirEntryBuf`] with a particular path,
+    /// normalized the same way as [`Dir::get_entry()`].
+    pub fn get_entry<S: AsRef<Path>>(&self, path: S) -> Option<&DirEntryBuf> {
+        crate::paths::lookup(path.as_ref(), |path| self.get_entry_normalized(path))
+    }
+
+    fn get_entry_normalized(&self, path: &str) -> Option<&DirEntryBuf> {
+        let mut names = self.relative(path)?.split('/');
+        let mut entry = &self.entries[self.position(names.next()?).ok()?];
+
+        for name in names {
+            let dir = entry.as_dir()?;
+            entry = &dir.entries[dir.position(name).ok()?];
+        }
+
+        Some(entry)
+    }
+
+    /// Look up a file by name.
+    pub fn get_file<S: AsRef<Path>>(&self, path: S) -> Option<&FileBuf> {
+        self.get_entry(path).and_then(DirEntryBuf::as_file)
+    }
+
+    /// Look up a dir by name.
+    pub fn get_dir<S: AsRef<Path>>(&self, path: S) -> Option<&DirBuf> {
+        self.get_entry(path).and_then(DirEntryBuf::as_dir)
+    }
+
+    /// Does this directory contain `path`?
+    pub fn contains<S: AsRef<Path>>(&self, path: S) -> bool {
+        self.get_entry(path).is_some()
+    }
+
+    /// Add a file to the tree, creating any parent directories and returning
+    /// the entry it replaced.
+    ///
+    /// The path is normalized the same way as [`Dir::get_entry()`]. If one of
+    /// the file's parents already exists as a file, it is replaced with a
+    /// directory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path normalizes to the root directory (e.g. `""` or
+    /// `"."`).
+    pub fn insert_file<P, C>(&mut self, path: P, contents: C) -> Option<DirEntryBuf>
+    where
+        P: AsRef<Path>,
+        C: Into<Vec<u8>>,
+    {
+        let path = crate::paths::normalize(path.as_ref())
+            .unwrap_or_else(|| panic!("{:?} isn't a valid file path", path.as_ref()));
+        let file = DirEntryBuf::File(FileBuf {
+            path: path.clone(),
+            contents: contents.into(),
+        });
+
+        let start = path.len() - self.relative(&path).unwrap_or(&path).len();
+        let mut dir = self;
+
+        for (ix, _) in path[start..].match_indices('/') {
+            let parent = &path[..start + ix];
+
+            let position = match dir.position(file_name(parent)) {
+                Ok(position) => position,
+                Err(position) => {
+                    let new_dir = DirEntryBuf::Dir(DirBuf {
+                        path: parent.to_string(),
+                        entries: Vec::new(),
+                    });
+                    dir.entries.insert(position, new_dir);
+                    position
+                }
+            };
+
+            if let DirEntryBuf::File(_) = dir.entries[position] {
+                dir.entries[position] = DirEntryBuf::Dir(DirBuf {
+                    path: parent.to_string(),
+                    entries: Vec::new(),
+                });
+            }
+
+            dir = match &mut dir.entries[position] {
+                DirEntryBuf::Dir(d) => d,
+                DirEntryBuf::File(_) => unreachable!(),
+            };
+        }
+
+        match dir.position(file_name(&path)) {
+            Ok(position) => Some(std::mem::replace(&mut dir.entries[position], file)),
+            Err(position) => {
+                dir.entries.insert(position, file);
+                None
+            }
+        }
+    }
+
+    /// Remove an entry from the tree, returning it if it existed.
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<DirEntryBuf> {
+        crate::paths::lookup(path.as_ref(), |path| self.remove_normalized(path))
+    }
+
+    fn remove_normalized(&mut self, path: &str) -> Option<DirEntryBuf> {
+        let rest = self.relative(path)?;
+        let (parents, name) = match rest.rfind('/') {
+            Some(ix) => (&rest[..ix], &rest[ix + 1..]),
+            None => ("", rest),
+        };
+        let mut dir = self;
+
+        for parent in parents.split('/').filter(|p| !p.is_empty()) {
+            let ix = dir.position(parent).ok()?;
+
+            dir = match &mut dir.entries[ix] {
+                DirEntryBuf::Dir(d) => d,
+                DirEntryBuf::File(_) => return None,
+            };
+        }
+
+        let ix = dir.position(name).ok()?;
+        Some(dir.entries.remove(ix))
+    }
+
+    /// Strip this directory's path from the front of `path`.
+    fn relative<'p>(&self, path: &'p str) -> Option<&'p str> {
+        if self.path.is_empty() {
+            Some(path)
+        } else {
+            path.strip_prefix(self.path.as_str())?.strip_prefix('/')
+        }
+    }
+
+    /// Find the index of the child called `name`.
+    ///
+    /// Returns `Err` with the index where `name` should be inserted if no
+    /// child matches.
+    fn position(&self, name: &str) -> Result<usize, usize> {
+        self.entries.binary_search_by(|e| e.name().cmp(name))
+    }
+
+    // children are sorted and looked up by name rather than by their full
+    // path, because a hand-written Dir may not prefix its children's paths
+    // with its own
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| a.name().cmp(b.name()));
+    }
+
+    /// Create directories and extract all files to the real filesystem, the
+    /// same way as [`Dir::extract()`].
+    pub fn extract<S: AsRef<Path>>(&self, base_path: S) -> io::Result<()> {
+        let base_path = base_path.as_ref();
+
+        for entry in self.entries() {
+            let path = base_path.join(entry.path());
+
+            match entry {
+                DirEntryBuf::Dir(d) => {
+                    fs::create_dir_all(&path)?;
+                    d.extract(base_path)?;
+                }
+                DirEntryBuf::File(f) => {
+                    fs::write(path, f.contents())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn read_dir(root: &Path, dir: &Path) -> io::Result<DirBuf> {
+    let mut children = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        children.push(entry?.path());
+    }
+    children.sort();
+
+    let mut entries = Vec::new();
+
+    for child in children {
+        let entry = if child.is_dir() {
+            DirEntryBuf::Dir(read_dir(root, &child)?)
+        } else if child.is_file() {
+            DirEntryBuf::File(FileBuf {
+                path: relative_path(root, &child)?,
+                contents: fs::read(&child)?,
+            })
+        } else {
+            continue;
+        };
+
+        entries.push(entry);
+    }
+
+    Ok(DirBuf {
+        path: relative_path(root, dir)?,
+        entries,
+    })
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn relative_path(root: &Path, path: &Path) -> io::Result<String> {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+
+    match relative.to_str() {
+        Some(s) => Ok(s.replace('\\', "/")),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{:?} is not valid UTF-8", path),
+        )),
+    }
+}
+
+impl<'a> From<&Dir<'a>> for DirBuf {
+    fn from(dir: &Dir<'a>) -> Self {
+        let mut buf = DirBuf {
+            path: dir.path().to_string_lossy().into_owned(),
+            entries: dir.entries().iter().map(DirEntryBuf::from).collect(),
+        };
+        buf.sort();
+        buf
+    }
+}
+
+/// An owned file, the counterpart to [`File`].
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct FileBuf {
+    path: String,
+    contents: Vec<u8>,
+}
+
+impl FileBuf {
+    /// The full path for this [`FileBuf`], relative to the root of the tree.
+    pub fn path(&self) -> &Path {
+        Path::new(&self.path)
+    }
+
+    /// The file's raw contents.
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+
+    /// The file's contents interpreted as a string.
+    pub fn contents_utf8(&self) -> Option<&str> {
+        std::str::from_utf8(self.contents()).ok()
+    }
+
+    /// Take ownership of the file's contents.
+    pub fn into_contents(self) -> Vec<u8> {
+        self.contents
+    }
+}
+
+impl std::fmt::Debug for FileBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileBuf")
+            .field("path", &self.path)
+            .field("contents", &format!("<{} bytes>", self.contents.len()))
+            .finish()
+    }
+}
+
+impl<'a> From<&File<'a>> for FileBuf {
+    fn from(file: &File<'a>) -> Self {
+        FileBuf {
+            path: file.path().to_string_lossy().into_owned(),
+            contents: file.contents().to_vec(),
+        }
+    }
+}
+
+/// An entry in a [`DirBuf`], the counterpart to [`DirEntry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirEntryBuf {
+    /// A directory.
+    Dir(DirBuf),
+    /// A file.
+    File(FileBuf),
+}
+
+impl DirEntryBuf {
+    /// The entry's full path.
+    pub fn path(&self) -> &Path {
+        Path::new(self.path_str())
+    }
+
+    fn path_str(&self) -> &str {
+        match self {
+            DirEntryBuf::Dir(d) => &d.path,
+            DirEntryBuf::File(f) => &f.path,
+        }
+    }
+
+    fn name(&self) -> &str {
+        file_name(self.path_str())
+    }
+
+    /// Try to get this as a [`DirBuf`], if it is one.
+    pub fn as_dir(&self) -> Option<&DirBuf> {
+        match self {
+            DirEntryBuf::Dir(d) => Some(d),
+            DirEntryBuf::File(_) => None,
+        }
+    }
+
+    /// Try to get this as a [`FileBuf`], if it is one.
+    pub fn as_file(&self) -> Option<&FileBuf> {
+        match self {
+            DirEntryBuf::File(f) => Some(f),
+            DirEntryBuf::Dir(_) => None,
+        }
+    }
+
+    /// Get this item's sub-items, if it has any.
+    pub fn children(&self) -> &[DirEntryBuf] {
+        match self {
+            DirEntryBuf::Dir(d) => d.entries(),
+            DirEntryBuf::File(_) => &[],
+        }
+    }
+}
+
+impl<'a> From<&DirEntry<'a>> for DirEntryBuf {
+    fn from(entry: &DirEntry<'a>) -> Self {
+        match entry {
+            DirEntry::Dir(d) => DirEntryBuf::Dir(DirBuf::from(d)),
+            DirEntry::File(f) => DirEntryBuf::File(FileBuf::from(f)),
+        }
+    }
+}
+
+impl<'a> From<ArchiveDir<'a>> for DirBuf {
+    fn from(dir: ArchiveDir<'a>) -> Self {
+        let mut buf = DirBuf {
+            path: dir.path().to_string_lossy().into_owned(),
+            entries: dir.entries().map(DirEntryBuf::from).collect(),
+        };
+        buf.sort();
+        buf
+    }
+}
+
+impl<'a> From<ArchiveEntry<'a>> for DirEntryBuf {
+    fn from(entry: ArchiveEntry<'a>) -> Self {
+        match entry {
+            ArchiveEntry::Dir(d) => DirEntryBuf::Dir(DirBuf::from(d)),
+            ArchiveEntry::File(f) => DirEntryBuf::File(FileBuf {
+                path: f.path().to_string_lossy().into_owned(),
+                contents: f.contents().to_vec(),
+            }),
+        }
+    }
+}
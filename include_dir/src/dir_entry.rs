@@ -42,4 +42,15 @@ impl<'a> DirEntry<'a> {
             DirEntry::File(_) => &[],
         }
     }
+
+    /// Get the entry's [`Metadata`], if available.
+    ///
+    /// [`Metadata`]: crate::Metadata
+    #[cfg(feature = "metadata")]
+    pub fn metadata(&self) -> Option<&crate::Metadata> {
+        match self {
+            DirEntry::Dir(d) => d.metadata(),
+            DirEntry::File(f) => f.metadata(),
+        }
+    }
 }
@@ -4,10 +4,15 @@ use std::{
 };
 
 /// A file with its contents stored in a `&'static [u8]`.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, Eq)]
 pub struct File<'a> {
     path: &'a str,
     contents: &'a [u8],
+    /// Were the contents created from a `&str`?
+    text: bool,
+    fingerprinted_path: Option<&'a str>,
+    /// The hash of `contents`, if it was computed at compile time.
+    hash: Option<u64>,
     #[cfg(feature = "metadata")]
     metadata: Option<crate::Metadata>,
 }
@@ -18,25 +23,172 @@ impl<'a> File<'a> {
         File {
             path,
             contents,
+            text: false,
+            fingerprinted_path: None,
+            hash: None,
             #[cfg(feature = "metadata")]
             metadata: None,
         }
     }
 
+    /// Create a new [`File`] whose contents are known to be text.
+    ///
+    /// This is what [`crate::include_dir!()`] generates for files which are
+    /// valid UTF-8.
+    pub const fn new_text(path: &'a str, contents: &'a str) -> Self {
+        File {
+            contents: contents.as_bytes(),
+            text: true,
+            ..File::new(path, &[])
+        }
+    }
+
+    /// Set the path this [`File`] can also be found at, which includes a hash
+    /// of its contents (e.g. `js/app.3f9a1c0d.js` for `js/app.js`).
+    ///
+    /// This is set by [`crate::include_dir!()`] when using
+    /// `fingerprint = true`.
+    pub const fn with_fingerprinted_path(self, fingerprinted_path: &'a str) -> Self {
+        File {
+            fingerprinted_path: Some(fingerprinted_path),
+            ..self
+        }
+    }
+
+    /// Set the hash of this [`File`]'s contents, so [`File::hash()`] doesn't
+    /// need to compute it.
+    ///
+    /// This is set by [`crate::include_dir!()`]. The hash must be the one
+    /// [`File::hash()`] would compute, otherwise manifests will be wrong.
+    #[doc(hidden)]
+    pub const fn with_hash(self, hash: u64) -> Self {
+        File {
+            hash: Some(hash),
+            ..self
+        }
+    }
+
+    /// The 64-bit FNV-1a hash of the file's contents, the same hash used by
+    /// [`crate::ArchiveFile::hash()`].
+    ///
+    /// Files embedded using [`crate::include_dir!()`]'s `fingerprint` option
+    /// already have their hash computed at compile time, otherwise it is
+    /// computed on every call.
+    pub fn hash(&self) -> u64 {
+        match self.hash {
+            Some(hash) => hash,
+            None => crate::archive::fnv1a(self.contents),
+        }
+    }
+
     /// The full path for this [`File`], relative to the directory passed to
     /// [`crate::include_dir!()`].
     pub fn path(&self) -> &'a Path {
         Path::new(self.path)
     }
 
+    /// The file's path with a hash of its contents inserted before the
+    /// extension, if it was embedded using `fingerprint = true`.
+    ///
+    /// Because the path changes whenever the contents do, it can be served
+    /// with far-future caching headers. See [`crate::Dir::asset_url()`].
+    pub fn fingerprinted_path(&self) -> Option<&'a Path> {
+        self.fingerprinted_path.map(Path::new)
+    }
+
     /// The file's raw contents.
-    pub fn contents(&self) -> &[u8] {
+    pub const fn contents(&self) -> &'a [u8] {
         self.contents
     }
 
+    /// Do the file's contents start at an address which is a multiple of
+    /// `align`?
+    ///
+    /// This is always `true` for alignments up to the one requested using
+    /// [`crate::include_dir!()`]'s `align` option.
+    ///
+    /// # Panics
+    ///
+    /// Like [`std::alloc::Layout`], `align` must be a power of two, so this
+    /// panics if it is `0` or any other value which isn't.
+    pub fn is_aligned_to(&self, align: usize) -> bool {
+        assert!(
+            align.is_power_of_two(),
+            "Alignment must be a power of two, not {}",
+            align
+        );
+
+        self.contents.as_ptr() as usize & (align - 1) == 0
+    }
+
+    /// Reinterpret the file's contents as a slice of `T` without copying
+    /// them, e.g. to read a table of `u32`s embedded using `align = 4`.
+    ///
+    /// This fails if the contents aren't aligned for `T` or their length
+    /// isn't a multiple of `T`'s size. Values are read using the target's
+    /// native endianness.
+    ///
+    /// ```rust,ignore
+    /// use include_dir::{include_file, File};
+    ///
+    /// static TABLE: File<'_> = include_file!("$CARGO_MANIFEST_DIR/assets/table.bin", align = 4);
+    ///
+    /// let table: &[u32] = TABLE.contents_as().unwrap();
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn contents_as<T: bytemuck::AnyBitPattern>(
+        &self,
+    ) -> Result<&'a [T], bytemuck::PodCastError> {
+        bytemuck::try_cast_slice(self.contents)
+    }
+
     /// The file's contents interpreted as a string.
-    pub fn contents_utf8(&self) -> Option<&str> {
-        std::str::from_utf8(self.contents()).ok()
+    ///
+    /// This only needs to validate the contents if the file wasn't embedded
+    /// as text (see [`File::as_str()`]).
+    pub fn contents_utf8(&self) -> Option<&'a str> {
+        self.as_str()
+            .or_else(|| std::str::from_utf8(self.contents()).ok())
+    }
+
+    /// Was this file embedded as text?
+    ///
+    /// [`crate::include_dir!()`] checks whether each file is valid UTF-8 at
+    /// compile time, so this is `true` for every embedded text file.
+    pub const fn is_text(&self) -> bool {
+        self.text
+    }
+
+    /// The contents of a file that was embedded as text, without validating
+    /// them again.
+    ///
+    /// Unlike [`File::contents_utf8()`], this is a constant-time field read.
+    /// It returns `None` for binary files and files which weren't created
+    /// using [`File::new_text()`].
+    pub const fn as_str(&self) -> Option<&'a str> {
+        if self.text {
+            // SAFETY: `text` is only set by `File::new_text()`, so the
+            // contents came from a `&str`
+            Some(unsafe { std::str::from_utf8_unchecked(self.contents) })
+        } else {
+            None
+        }
+    }
+
+    /// The contents of a file that was embedded as text.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the file wasn't embedded as text (see
+    /// [`File::is_text()`]). Use [`File::contents_utf8()`] for other files.
+    pub fn contents_str(&self) -> &'a str {
+        match self.as_str() {
+            Some(text) => text,
+            None => panic!(
+                "\"{}\" wasn't embedded as text, so its contents might not be valid UTF-8",
+                self.path
+            ),
+        }
     }
 }
 
@@ -44,12 +196,9 @@ impl<'a> File<'a> {
 impl<'a> File<'a> {
     /// Set the [`Metadata`] associated with a [`File`].
     pub const fn with_metadata(self, metadata: crate::Metadata) -> Self {
-        let File { path, contents, .. } = self;
-
         File {
-            path,
-            contents,
             metadata: Some(metadata),
+            ..self
         }
     }
 
@@ -64,6 +213,9 @@ impl<'a> Debug for File<'a> {
         let File {
             path,
             contents,
+            text,
+            fingerprinted_path,
+            hash,
             #[cfg(feature = "metadata")]
             metadata,
         } = self;
@@ -71,7 +223,10 @@ impl<'a> Debug for File<'a> {
         let mut d = f.debug_struct("File");
 
         d.field("path", path)
-            .field("contents", &format!("<{} bytes>", contents.len()));
+            .field("contents", &format!("<{} bytes>", contents.len()))
+            .field("text", text)
+            .field("fingerprinted_path", fingerprinted_path)
+            .field("hash", hash);
 
         #[cfg(feature = "metadata")]
         d.field("metadata", metadata);
@@ -79,3 +234,28 @@ impl<'a> Debug for File<'a> {
         d.finish()
     }
 }
+
+// whether a file is known to be text and its hash are derived from its
+// contents, so they shouldn't affect equality
+impl PartialEq for File<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let File {
+            path,
+            contents,
+            text: _,
+            fingerprinted_path,
+            hash: _,
+            #[cfg(feature = "metadata")]
+            metadata,
+        } = self;
+
+        #[cfg(feature = "metadata")]
+        if *metadata != other.metadata {
+            return false;
+        }
+
+        *path == other.path
+            && *contents == other.contents
+            && *fingerprinted_path == other.fingerprinted_path
+    }
+}
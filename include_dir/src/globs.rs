@@ -1,4 +1,4 @@
-use crate::{Dir, DirEntry};
+use crate::{Dir, DirEntry, Overlay, OverlayEntry};
 use glob::{Pattern, PatternError};
 
 impl<'a> Dir<'a> {
@@ -10,6 +10,17 @@ impl<'a> Dir<'a> {
     }
 }
 
+impl<'a> Overlay<'a> {
+    /// Search every layer for a file or directory with a glob pattern.
+    pub fn find(&self, glob: &str) -> Result<impl Iterator<Item = OverlayEntry>, PatternError> {
+        let pattern = Pattern::new(glob)?;
+
+        Ok(self
+            .walk()
+            .filter(move |entry| pattern.matches_path(entry.path())))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct Globs<'a> {
     stack: Vec<&'a DirEntry<'a>>,
@@ -43,19 +43,95 @@
 //! }
 //! ```
 //!
+//! Several directories can be combined into one tree by mapping mount points
+//! to paths. The `on_conflict` option (`"error"`, `"first"` or `"last"`)
+//! decides what happens when more than one directory provides the same path.
+//!
+//! ```rust
+//! use include_dir::{include_dir, Dir};
+//!
+//! static SOURCE: Dir<'_> = include_dir!({
+//!     "" => "$CARGO_MANIFEST_DIR/src",
+//!     "tests" => "$CARGO_MANIFEST_DIR/tests",
+//! });
+//!
+//! assert!(SOURCE.contains("lib.rs"));
+//! assert!(SOURCE.contains("tests/integration_test.rs"));
+//! ```
+//!
+//! If you only need a single file, the [`include_file!()`] macro embeds it as a
+//! [`File`] so it can be used with the same API.
+//!
+//! ```rust
+//! use include_dir::{include_file, File};
+//!
+//! static CARGO_TOML: File<'_> = include_file!("$CARGO_MANIFEST_DIR/Cargo.toml");
+//!
+//! assert_eq!(CARGO_TOML.path().to_str(), Some("Cargo.toml"));
+//! ```
+//!
+//! For large trees, or plugins which are loaded at runtime, a directory can
+//! also be stored as a single binary archive. The [`include_archive!()`]
+//! macro embeds one at compile time, [`Dir::to_archive_bytes()`] creates one
+//! at runtime, and [`ArchiveDir`] reads it without copying.
+//!
+//! ```rust
+//! use include_dir::{include_archive, ArchiveDir};
+//!
+//! static TESTS: &[u8] = include_archive!("$CARGO_MANIFEST_DIR/tests");
+//!
+//! let tests = ArchiveDir::from_bytes(TESTS).unwrap();
+//! assert!(tests.contains("integration_test.rs"));
+//! ```
+//!
 //! # Features
 //!
 //! This library exposes a couple feature flags for enabling and disabling extra
 //! functionality. These are:
 //!
 //! - `glob` - search for files using glob patterns
-//! - `metadata` - include some basic filesystem metadata like last modified
-//!   time. This is not enabled by default to allow for more reproducible builds
-//!   and to hide potentially identifying information.
+//! - `build` - preprocess a directory from a build script using `Builder`
+//!   (e.g. to minify files or convert Markdown to HTML) before embedding it
+//! - `metadata` - the APIs for reading basic filesystem metadata for files
+//!   and directories, like their last modified time, size and Unix
+//!   permissions. Metadata is only embedded by invocations which opt in using
+//!   `include_dir!("...", metadata = true)`, so enabling the feature in one
+//!   crate won't affect the rest of the build. Fields the platform doesn't
+//!   provide are left empty. If you need metadata and reproducible builds, set
+//!   `SOURCE_DATE_EPOCH` and see the `metadata_accessed` and
+//!   `metadata_modified` options on [`include_dir!()`].
+//! - `nfc` - allows passing `normalize_unicode = true` to [`include_dir!()`]
+//!   to convert embedded paths to Unicode Normalization Form C, so a file
+//!   named on macOS (which uses decomposed characters) can be found using the
+//!   composed form. Lookups also fall back to the composed form of the path
+//!   they are given
+//! - `json`, `toml` and `yaml` - deserialize embedded files with methods like
+//!   [`File::parse_json()`], or every file matching a glob pattern with
+//!   `Dir::load_all()` (which also needs the `glob` feature). Passing
+//!   `validate_syntax = true` to [`include_dir!()`] also makes any file with
+//!   a `.json`, `.toml`, `.yaml` or `.yml` extension that fails to parse a
+//!   compile error, as is embedding a format whose feature isn't enabled
+//! - `serde` - serialize the listing returned by [`Dir::manifest()`]
+//! - `bytemuck` - read a file embedded using the `align` option as a slice
+//!   of numbers or other plain-old-data types with `File::contents_as()`
+//! - `vfs` - use a [`Dir`] as a read-only [`vfs::FileSystem`], so it can be
+//!   passed to libraries that accept a virtual filesystem
+//! - `tera`, `handlebars` and `minijinja` - register every template in a
+//!   [`Dir`] with a template engine, named after its path relative to the
+//!   [`Dir`], using `Templates`
+//! - `migrations` - discover SQL migrations in a [`Dir`] with
+//!   `Dir::migrations()`. Passing `validate_migrations = true` to
+//!   [`include_dir!()`] checks their names and versions at compile time
+//! - `rusqlite` - apply and revert migrations on a SQLite database
+//! - `fluent` - load [Fluent](https://projectfluent.org/) translations from a
+//!   `<locale>/*.ftl` tree and negotiate the best locale with `LocaleDir`.
+//!   Passing `validate_locales = true` to [`include_dir!()`] checks that
+//!   every locale has the same `.ftl` files at compile time
 //! - `nightly` - enables nightly APIs like [`track_path`][track-path]
 //!   and  [`proc_macro_tracked_env`][tracked-env]. This gives the compiler
 //!   more information about what is accessed by the procedural macro, enabling
-//!   better caching. **Functionality behind this feature flag is unstable and
+//!   better caching. It also lets `size_report = true` emit a normal warning
+//!   using [`proc_macro_diagnostic`][diagnostic]. **Functionality behind this feature flag is unstable and
 //!   may change or stop compiling at any time.**
 //!
 //! # Compile Time Considerations
@@ -76,8 +152,43 @@
 //! Using `include_dir!("target/")` increased the compile time to 5 seconds
 //! and used 730MB of RAM, generating a 72MB binary.
 //!
+//! If compile times matter, [`include_archive!()`] emits the tree as one byte
+//! string in the archive format, which is read at runtime using
+//! [`ArchiveDir`], so there is nothing for the compiler to evaluate.
+//!
+//! Measured with rustc 1.95 on Linux using `scripts/bench_codegen.py` (a debug
+//! build of a binary that only embeds the directory):
+//!
+//! | Fixture                        | Strategy             | Time | Peak RAM |
+//! |--------------------------------|----------------------|------|----------|
+//! | 5,000 files, 20 MB in 50 dirs  | `include_dir!()`     | 1.9s | 345MB    |
+//! |                                | `include_archive!()` | 1.0s | 229MB    |
+//! | 20,000 files, 2 MB in 400 dirs | `include_dir!()`     | 5.2s | 388MB    |
+//! |                                | `include_archive!()` | 0.6s | 130MB    |
+//!
+//! Concatenating every file into one blob and emitting a flat table of
+//! entries for [`Dir`] and [`File`] to borrow from was also measured, and
+//! isn't offered: the compiler takes longer to evaluate one large table than
+//! the nested expressions `include_dir!()` generates (8.3s and 945MB for the
+//! 20,000 file fixture), so it was slower than both of the above.
+//!
+//! To stop a stray dataset from sneaking into a build, the `max_file_size`,
+//! `max_total_size` and `max_files` options turn an unexpectedly large
+//! directory into a compile error listing the biggest offenders, and
+//! `size_report = true` emits a warning summarising what is being embedded:
+//!
+//! ```rust,ignore
+//! static ASSETS: Dir<'_> = include_dir!(
+//!     "$CARGO_MANIFEST_DIR/assets",
+//!     max_file_size = "5 MiB",
+//!     max_total_size = "50 MiB",
+//!     max_files = 2000,
+//! );
+//! ```
+//!
 //! [tracked-env]: https://github.com/rust-lang/rust/issues/74690
 //! [track-path]: https://github.com/rust-lang/rust/issues/73921
+//! [diagnostic]: https://github.com/rust-lang/rust/issues/54140
 //! [cargo-vars]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
 
 #![deny(
@@ -90,9 +201,15 @@
 )]
 #![cfg_attr(feature = "nightly", feature(doc_cfg))]
 
+mod archive;
+mod asset_source;
 mod dir;
+mod dir_buf;
 mod dir_entry;
 mod file;
+mod manifest;
+mod overlay;
+mod paths;
 
 #[cfg(feature = "metadata")]
 mod metadata;
@@ -100,11 +217,56 @@ mod metadata;
 #[cfg(feature = "glob")]
 mod globs;
 
+#[cfg(feature = "build")]
+mod builder;
+
+#[cfg(feature = "vfs")]
+mod virtual_fs;
+
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod deserialize;
+
+#[cfg(any(feature = "tera", feature = "handlebars", feature = "minijinja"))]
+mod templates;
+
+#[cfg(feature = "migrations")]
+mod migrations;
+
+#[cfg(feature = "fluent")]
+mod locales;
+
 #[cfg(feature = "metadata")]
 pub use crate::metadata::Metadata;
 
-pub use crate::{dir::Dir, dir_entry::DirEntry, file::File};
-pub use include_dir_macros::include_dir;
+#[cfg(feature = "build")]
+pub use crate::builder::Builder;
+
+#[cfg(feature = "fluent")]
+pub use crate::locales::{LocaleDir, LocaleError};
+
+#[cfg(feature = "migrations")]
+pub use crate::migrations::{Migration, MigrationError, MigrationErrorKind, Migrations};
+
+#[cfg(feature = "rusqlite")]
+pub use crate::migrations::ApplyError;
+
+#[cfg(any(feature = "tera", feature = "handlebars", feature = "minijinja"))]
+pub use crate::templates::Templates;
+
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub use crate::deserialize::{ParseError, ParseErrorKind};
+
+pub use crate::{
+    archive::{ArchiveDir, ArchiveEntry, ArchiveError, ArchiveFile},
+    asset_source::AssetSource,
+    dir::Dir,
+    dir_buf::{DirBuf, DirEntryBuf, FileBuf},
+    dir_entry::DirEntry,
+    file::File,
+    manifest::{Manifest, ManifestEntry},
+    overlay::{Overlay, OverlayEntry, OverlayFile},
+};
+pub use include_dir_macros::{include_archive, include_dir, include_file};
 
 #[doc = include_str!("../README.md")]
 #[allow(dead_code)]
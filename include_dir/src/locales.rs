@@ -0,0 +1,234 @@
+use crate::Dir;
+use fluent_bundle::{FluentBundle, FluentError, FluentResource};
+use fluent_langneg::{negotiate_languages, NegotiationStrategy};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+use unic_langid::LanguageIdentifier;
+
+/// An embedded directory of [Fluent](https://projectfluent.org/)
+/// translations, laid out as `<locale>/**/*.ftl`.
+///
+/// ```rust
+/// use include_dir::{include_dir, Dir, LocaleDir};
+///
+/// static LOCALES: Dir<'_> = include_dir!(
+///     "$CARGO_MANIFEST_DIR/tests/fixtures/locales",
+///     validate_locales = true,
+/// );
+///
+/// let locales = LocaleDir::new(&LOCALES);
+/// let bundle = locales.bundle_for(&["fr-CA", "fr", "en"]).unwrap();
+///
+/// let message = bundle.get_message("hello").unwrap();
+/// let mut errors = Vec::new();
+/// let greeting = bundle.format_pattern(message.value().unwrap(), None, &mut errors);
+/// assert_eq!(greeting, "Bonjour !");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocaleDir<'a> {
+    dir: &'a Dir<'a>,
+}
+
+impl<'a> LocaleDir<'a> {
+    /// Create a [`LocaleDir`] where each sub-directory of `dir` is named after
+    /// a locale (e.g. `en-US/` or `fr/`).
+    pub const fn new(dir: &'a Dir<'a>) -> Self {
+        LocaleDir { dir }
+    }
+
+    /// The locales that have translations, sorted by directory name.
+    ///
+    /// Sub-directories whose names aren't valid language identifiers are
+    /// skipped.
+    pub fn locales(&self) -> Vec<LanguageIdentifier> {
+        self.locale_dirs().into_iter().map(|(id, _)| id).collect()
+    }
+
+    fn locale_dirs(&self) -> Vec<(LanguageIdentifier, &'a Dir<'a>)> {
+        self.dir
+            .dirs()
+            .filter_map(|d| {
+                let name = d.path().file_name()?.to_str()?;
+                let id = name.parse().ok()?;
+                Some((id, d))
+            })
+            .collect()
+    }
+
+    /// Parse every `.ftl` file for a particular locale, sorted by path.
+    pub fn resources(
+        &self,
+        locale: &LanguageIdentifier,
+    ) -> Result<Vec<FluentResource>, LocaleError> {
+        let dir = self
+            .locale_dirs()
+            .into_iter()
+            .find(|(id, _)| id == locale)
+            .map(|(_, d)| d)
+            .ok_or(LocaleError::NoMatchingLocale)?;
+
+        Ok(load_resources(dir)?
+            .into_iter()
+            .map(|(_, resource)| resource)
+            .collect())
+    }
+
+    /// Negotiate the best available locales for the `requested` ones (in
+    /// order of preference), and assemble their translations into a
+    /// [`FluentBundle`].
+    ///
+    /// Messages are taken from the best matching locale, falling back to the
+    /// next best locale for messages it doesn't translate. Requested locales
+    /// which aren't valid language identifiers are ignored, but it is an
+    /// error for two files in the same locale to define the same message.
+    pub fn bundle_for<S: AsRef<str>>(
+        &self,
+        requested: &[S],
+    ) -> Result<FluentBundle<FluentResource>, LocaleError> {
+        let requested: Vec<LanguageIdentifier> = requested
+            .iter()
+            .filter_map(|s| s.as_ref().parse().ok())
+            .collect();
+
+        let locale_dirs = self.locale_dirs();
+        let available: Vec<&LanguageIdentifier> = locale_dirs.iter().map(|(id, _)| id).collect();
+
+        let supported =
+            negotiate_languages(&requested, &available, None, NegotiationStrategy::Filtering);
+
+        if supported.is_empty() {
+            return Err(LocaleError::NoMatchingLocale);
+        }
+
+        let mut bundle = FluentBundle::new(supported.iter().map(|id| (**id).clone()).collect());
+
+        for id in supported {
+            let (_, dir) = locale_dirs
+                .iter()
+                .find(|(candidate, _)| candidate == *id)
+                .expect("negotiation only returns available locales");
+
+            let resources = load_resources(dir)?;
+            check_duplicates(id, &resources)?;
+
+            for (_, resource) in resources {
+                // Messages which were already added by a better matching
+                // locale are kept, so "overriding" errors are expected
+                let _ = bundle.add_resource(resource);
+            }
+        }
+
+        Ok(bundle)
+    }
+}
+
+/// Make sure no two files in one locale define the same message or term,
+/// which would otherwise be hidden by the errors expected when falling back.
+fn check_duplicates(
+    locale: &LanguageIdentifier,
+    resources: &[(PathBuf, FluentResource)],
+) -> Result<(), LocaleError> {
+    let mut bundle = FluentBundle::new(vec![locale.clone()]);
+
+    for (path, resource) in resources {
+        bundle
+            .add_resource(resource)
+            .map_err(|errors| LocaleError::Duplicate {
+                path: path.clone(),
+                errors,
+            })?;
+    }
+
+    Ok(())
+}
+
+fn load_resources(dir: &Dir<'_>) -> Result<Vec<(PathBuf, FluentResource)>, LocaleError> {
+    let files = dir
+        .walk()
+        .filter_map(crate::DirEntry::as_file)
+        .filter(|f| f.path().extension().map_or(false, |ext| ext == "ftl"));
+
+    files
+        .map(|f| {
+            let source = std::str::from_utf8(f.contents()).map_err(|error| LocaleError::Utf8 {
+                path: f.path().to_path_buf(),
+                error,
+            })?;
+
+            let resource = FluentResource::try_new(source.to_string()).map_err(|(_, errors)| {
+                LocaleError::Syntax {
+                    path: f.path().to_path_buf(),
+                    errors: errors.into_iter().map(FluentError::ParserError).collect(),
+                }
+            })?;
+
+            Ok((f.path().to_path_buf(), resource))
+        })
+        .collect()
+}
+
+/// The error returned when translations can't be loaded from a
+/// [`LocaleDir`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LocaleError {
+    /// None of the requested locales are available.
+    NoMatchingLocale,
+    /// A `.ftl` file isn't valid UTF-8.
+    Utf8 {
+        /// The file's path.
+        path: PathBuf,
+        /// The underlying error.
+        error: std::str::Utf8Error,
+    },
+    /// A `.ftl` file contains syntax errors.
+    Syntax {
+        /// The file's path.
+        path: PathBuf,
+        /// The problems that were found.
+        errors: Vec<FluentError>,
+    },
+    /// A `.ftl` file defines messages or terms which another file in the
+    /// same locale already defines.
+    Duplicate {
+        /// The file's path.
+        path: PathBuf,
+        /// The definitions that were duplicated.
+        errors: Vec<FluentError>,
+    },
+}
+
+impl Display for LocaleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LocaleError::NoMatchingLocale => {
+                write!(f, "None of the requested locales are available")
+            }
+            LocaleError::Utf8 { path, .. } => write!(f, "\"{}\" isn't valid UTF-8", path.display()),
+            LocaleError::Syntax { path, errors } => write!(
+                f,
+                "Unable to parse \"{}\" ({} errors)",
+                path.display(),
+                errors.len()
+            ),
+            LocaleError::Duplicate { path, errors } => write!(
+                f,
+                "\"{}\" redefines {} messages from the same locale",
+                path.display(),
+                errors.len()
+            ),
+        }
+    }
+}
+
+impl Error for LocaleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LocaleError::Utf8 { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{Dir, DirEntry};
+use std::path::Path;
+
+/// A listing of every file in a [`Dir`], created using [`Dir::manifest()`].
+///
+/// With the `serde` feature enabled, this can be serialized (e.g. to serve an
+/// `/assets/manifest.json` endpoint). Hashes are written as 16 hexadecimal
+/// digits because JavaScript can't represent every `u64` exactly.
+///
+/// ```rust
+/// use include_dir::{include_dir, Dir};
+///
+/// static TESTS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests");
+///
+/// let manifest = TESTS.manifest();
+/// println!(
+///     "serving {} embedded assets ({} bytes)",
+///     manifest.file_count(),
+///     manifest.total_size(),
+/// );
+///
+/// let entry = manifest.get("fixtures/data/server.json").unwrap();
+/// assert_eq!(entry.mime(), "application/json");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest<'a> {
+    files: Vec<ManifestEntry<'a>>,
+    total_size: u64,
+    /// Are `files` sorted by path, so they can be binary searched? This is
+    /// always the case for a [`Dir`] generated by [`crate::include_dir!()`].
+    sorted: bool,
+}
+
+impl<'a> Manifest<'a> {
+    pub(crate) fn new(dir: &Dir<'a>) -> Self {
+        let files: Vec<_> = dir
+            .walk()
+            .filter_map(DirEntry::as_file)
+            .map(|f| ManifestEntry {
+                path: f.path(),
+                size: f.contents().len() as u64,
+                hash: f.hash(),
+                mime: mime_type(f.path()),
+            })
+            .collect();
+        let sorted = files.windows(2).all(|pair| pair[0].path < pair[1].path);
+
+        Manifest {
+            files,
+            total_size: dir.total_size(),
+            sorted,
+        }
+    }
+
+    /// Iterate over every file, in the same order as [`Dir::walk()`].
+    pub fn files(&self) -> impl Iterator<Item = &ManifestEntry<'a>> + '_ {
+        self.files.iter()
+    }
+
+    /// Look up a file's entry by its exact path.
+    pub fn get<S: AsRef<Path>>(&self, path: S) -> Option<&ManifestEntry<'a>> {
+        let path = path.as_ref();
+
+        if self.sorted {
+            let ix = self.files.binary_search_by(|f| f.path.cmp(path)).ok()?;
+            Some(&self.files[ix])
+        } else {
+            self.files.iter().find(|f| f.path == path)
+        }
+    }
+
+    /// The number of files in the manifest.
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The combined size of every file, in bytes.
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+}
+
+/// A single file in a [`Manifest`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ManifestEntry<'a> {
+    path: &'a Path,
+    size: u64,
+    hash: u64,
+    mime: &'static str,
+}
+
+impl<'a> ManifestEntry<'a> {
+    /// The file's full path, the same as [`crate::File::path()`]. Like every
+    /// other path, this is relative to the root of the embedded tree rather
+    /// than the [`Dir`] the manifest was created from.
+    pub fn path(&self) -> &'a Path {
+        self.path
+    }
+
+    /// The file's size in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The 64-bit FNV-1a hash of the file's contents, the same as
+    /// [`crate::File::hash()`].
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The file's MIME type, guessed from its extension.
+    ///
+    /// Unknown extensions are `application/octet-stream`.
+    pub fn mime(&self) -> &'static str {
+        self.mime
+    }
+}
+
+/// Guess a file's MIME type from its extension.
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("html") | Some("htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") | Some("mjs") => "text/javascript",
+        Some("json") | Some("map") => "application/json",
+        Some("wasm") => "application/wasm",
+        Some("txt") | Some("md") | Some("rs") | Some("toml") | Some("ftl") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("xml") => "application/xml",
+        Some("yaml") | Some("yml") => "application/yaml",
+        Some("sql") => "application/sql",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use super::{Manifest, ManifestEntry};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    impl Serialize for Manifest<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("Manifest", 3)?;
+            s.serialize_field("file_count", &self.file_count())?;
+            s.serialize_field("total_size", &self.total_size)?;
+            s.serialize_field("files", &self.files)?;
+            s.end()
+        }
+    }
+
+    impl Serialize for ManifestEntry<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("ManifestEntry", 4)?;
+            s.serialize_field("path", &self.path)?;
+            s.serialize_field("size", &self.size)?;
+            s.serialize_field("hash", &format!("{:016x}", self.hash))?;
+            s.serialize_field("mime", &self.mime)?;
+            s.end()
+        }
+    }
+}
@@ -1,42 +1,127 @@
 use std::time::{Duration, SystemTime};
 
-/// Basic metadata for a file.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// Basic filesystem metadata for a file or directory.
+///
+/// Every field is optional because platforms and filesystems don't always
+/// provide them (e.g. many Linux filesystems don't record when a file was
+/// created).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub struct Metadata {
-    accessed: Duration,
-    created: Duration,
-    modified: Duration,
+    accessed: Option<Duration>,
+    created: Option<Duration>,
+    modified: Option<Duration>,
+    len: u64,
+    mode: Option<u32>,
+    uid: Option<u32>,
+    gid: Option<u32>,
 }
 
 impl Metadata {
-    /// Create a new [`Metadata`] using the number of seconds since the
+    /// Create an empty [`Metadata`], where nothing is known.
+    pub const fn new() -> Self {
+        Metadata {
+            accessed: None,
+            created: None,
+            modified: None,
+            len: 0,
+            mode: None,
+            uid: None,
+            gid: None,
+        }
+    }
+
+    /// Set the time this entry was last accessed, as the time since the
+    /// [`SystemTime::UNIX_EPOCH`].
+    pub const fn with_accessed(self, since_epoch: Duration) -> Self {
+        Metadata {
+            accessed: Some(since_epoch),
+            ..self
+        }
+    }
+
+    /// Set the time this entry was created, as the time since the
     /// [`SystemTime::UNIX_EPOCH`].
-    pub const fn new(accessed: Duration, created: Duration, modified: Duration) -> Self {
+    pub const fn with_created(self, since_epoch: Duration) -> Self {
+        Metadata {
+            created: Some(since_epoch),
+            ..self
+        }
+    }
+
+    /// Set the time this entry was last modified, as the time since the
+    /// [`SystemTime::UNIX_EPOCH`].
+    pub const fn with_modified(self, since_epoch: Duration) -> Self {
+        Metadata {
+            modified: Some(since_epoch),
+            ..self
+        }
+    }
+
+    /// Set the entry's size in bytes.
+    pub const fn with_len(self, len: u64) -> Self {
+        Metadata { len, ..self }
+    }
+
+    /// Set the entry's Unix permissions and file type bits.
+    pub const fn with_mode(self, mode: u32) -> Self {
         Metadata {
-            accessed,
-            created,
-            modified,
+            mode: Some(mode),
+            ..self
         }
     }
 
-    /// Get the time this file was last accessed.
+    /// Set the IDs of the user and group that own the entry.
+    pub const fn with_owner(self, uid: u32, gid: u32) -> Self {
+        Metadata {
+            uid: Some(uid),
+            gid: Some(gid),
+            ..self
+        }
+    }
+
+    /// Get the time this entry was last accessed.
     ///
     /// See also: [`std::fs::Metadata::accessed()`].
-    pub fn accessed(&self) -> SystemTime {
-        SystemTime::UNIX_EPOCH + self.accessed
+    pub fn accessed(&self) -> Option<SystemTime> {
+        self.accessed.map(|d| SystemTime::UNIX_EPOCH + d)
     }
 
-    /// Get the time this file was created.
+    /// Get the time this entry was created.
     ///
     /// See also: [`std::fs::Metadata::created()`].
-    pub fn created(&self) -> SystemTime {
-        SystemTime::UNIX_EPOCH + self.created
+    pub fn created(&self) -> Option<SystemTime> {
+        self.created.map(|d| SystemTime::UNIX_EPOCH + d)
     }
 
-    /// Get the time this file was last modified.
+    /// Get the time this entry was last modified.
     ///
     /// See also: [`std::fs::Metadata::modified()`].
-    pub fn modified(&self) -> SystemTime {
-        SystemTime::UNIX_EPOCH + self.modified
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified.map(|d| SystemTime::UNIX_EPOCH + d)
+    }
+
+    /// The size of a file's contents in bytes, or `0` for a directory.
+    ///
+    /// See also: [`std::fs::Metadata::len()`].
+    #[allow(clippy::len_without_is_empty)]
+    pub const fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// The entry's Unix permissions and file type bits, if known.
+    ///
+    /// See also: `std::os::unix::fs::MetadataExt::mode()`.
+    pub const fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    /// The ID of the user that owns the entry, if known.
+    pub const fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    /// The ID of the group that owns the entry, if known.
+    pub const fn gid(&self) -> Option<u32> {
+        self.gid
     }
 }
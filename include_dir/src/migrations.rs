@@ -0,0 +1,510 @@
+use crate::{Dir, File};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+impl<'a> Dir<'a> {
+    /// Treat this directory as a set of SQL migrations.
+    ///
+    /// Two layouts are recognised, and may be mixed:
+    ///
+    /// - `V{version}__{name}.sql` files, with an optional
+    ///   `U{version}__{name}.sql` file to undo the migration
+    /// - `{version}_{name}/` directories containing an `up.sql` file and an
+    ///   optional `down.sql` file, where the version may contain dashes (e.g.
+    ///   `2023-01-31-120000_create_users/`)
+    ///
+    /// Files without a `.sql` extension and directories whose names don't
+    /// start with a digit are ignored, but any other `.sql` file or
+    /// directory which doesn't follow one of these layouts is an
+    /// [`MigrationErrorKind::InvalidName`] error. Passing
+    /// `validate_migrations = true` to [`crate::include_dir!()`] performs the
+    /// same checks at compile time.
+    ///
+    /// ```rust
+    /// use include_dir::{include_dir, Dir};
+    ///
+    /// static MIGRATIONS: Dir<'_> = include_dir!(
+    ///     "$CARGO_MANIFEST_DIR/tests/fixtures/migrations",
+    ///     validate_migrations = true,
+    /// );
+    ///
+    /// let migrations = MIGRATIONS.migrations().unwrap();
+    /// let names: Vec<_> = migrations.iter().map(|m| m.name()).collect();
+    /// assert_eq!(names, ["create_users", "add_email", "create_posts"]);
+    /// ```
+    pub fn migrations(&self) -> Result<Migrations<'a>, MigrationError> {
+        let mut migrations: BTreeMap<u64, Migration<'a>> = BTreeMap::new();
+        let mut downs = Vec::new();
+
+        for file in self.files() {
+            let file_name = file_name(file.path());
+            if !file_name.ends_with(".sql") {
+                continue;
+            }
+
+            let (kind, version, name) = parse_file_name(file_name)
+                .ok_or_else(|| MigrationError::new(file.path(), MigrationErrorKind::InvalidName))?;
+
+            if kind == 'U' {
+                downs.push((version, file));
+                continue;
+            }
+
+            let migration = Migration::new(version, name, file, None)?;
+            insert(&mut migrations, migration, file.path())?;
+        }
+
+        for dir in self.dirs() {
+            let dir_name = file_name(dir.path());
+            if !dir_name.starts_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+
+            let (version, name) = parse_dir_name(dir_name)
+                .ok_or_else(|| MigrationError::new(dir.path(), MigrationErrorKind::InvalidName))?;
+
+            let up = dir
+                .get_file_strict(dir.path().join("up.sql"))
+                .ok_or_else(|| MigrationError::new(dir.path(), MigrationErrorKind::MissingUp))?;
+            let down = dir.get_file_strict(dir.path().join("down.sql"));
+
+            let migration = Migration::new(version, name, up, down)?;
+            insert(&mut migrations, migration, dir.path())?;
+        }
+
+        for (version, file) in downs {
+            let migration = migrations
+                .get_mut(&version)
+                .ok_or_else(|| MigrationError::new(file.path(), MigrationErrorKind::MissingUp))?;
+
+            if migration.down.is_some() {
+                return Err(MigrationError::new(
+                    file.path(),
+                    MigrationErrorKind::DuplicateVersion(version),
+                ));
+            }
+
+            migration.down = Some(sql(file)?);
+        }
+
+        Ok(Migrations {
+            migrations: migrations.into_values().collect(),
+        })
+    }
+}
+
+fn insert<'a>(
+    migrations: &mut BTreeMap<u64, Migration<'a>>,
+    migration: Migration<'a>,
+    path: &Path,
+) -> Result<(), MigrationError> {
+    let version = migration.version;
+
+    if migrations.insert(version, migration).is_some() {
+        return Err(MigrationError::new(
+            path,
+            MigrationErrorKind::DuplicateVersion(version),
+        ));
+    }
+
+    Ok(())
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+}
+
+/// Parse `V1__name.sql` or `U1__name.sql` into its kind, version and name.
+fn parse_file_name(file_name: &str) -> Option<(char, u64, &str)> {
+    let kind = file_name
+        .chars()
+        .next()
+        .filter(|c| *c == 'V' || *c == 'U')?;
+    let (version, name) = file_name[1..].strip_suffix(".sql")?.split_once("__")?;
+
+    if name.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((kind, version.parse().ok()?, name))
+}
+
+/// Parse `2023-01-31-120000_name` into its version and name.
+fn parse_dir_name(dir_name: &str) -> Option<(u64, &str)> {
+    let (version, name) = dir_name.split_once('_')?;
+
+    if name.is_empty() || !version.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+        return None;
+    }
+
+    Some((version.replace('-', "").parse().ok()?, name))
+}
+
+fn sql<'a>(file: &File<'a>) -> Result<&'a str, MigrationError> {
+    std::str::from_utf8(file.contents())
+        .map_err(|e| MigrationError::new(file.path(), MigrationErrorKind::Utf8(e)))
+}
+
+/// The migrations found in a [`Dir`], sorted by version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migrations<'a> {
+    migrations: Vec<Migration<'a>>,
+}
+
+impl<'a> Migrations<'a> {
+    /// Iterate over the migrations in the order they should be applied.
+    pub fn iter(&self) -> impl Iterator<Item = &Migration<'a>> + '_ {
+        self.migrations.iter()
+    }
+
+    /// Look up a migration by its version.
+    pub fn get(&self, version: u64) -> Option<&Migration<'a>> {
+        self.migrations.iter().find(|m| m.version == version)
+    }
+
+    /// The number of migrations.
+    pub fn len(&self) -> usize {
+        self.migrations.len()
+    }
+
+    /// Are there no migrations?
+    pub fn is_empty(&self) -> bool {
+        self.migrations.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for Migrations<'a> {
+    type Item = Migration<'a>;
+    type IntoIter = std::vec::IntoIter<Migration<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.migrations.into_iter()
+    }
+}
+
+/// A single SQL migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration<'a> {
+    version: u64,
+    name: &'a str,
+    up: &'a str,
+    down: Option<&'a str>,
+    checksum: [u8; 32],
+}
+
+impl<'a> Migration<'a> {
+    fn new(
+        version: u64,
+        name: &'a str,
+        up: &File<'a>,
+        down: Option<&File<'a>>,
+    ) -> Result<Self, MigrationError> {
+        let up = sql(up)?;
+        let down = down.map(sql).transpose()?;
+
+        Ok(Migration {
+            version,
+            name,
+            up,
+            down,
+            checksum: Sha256::digest(up.as_bytes()).into(),
+        })
+    }
+
+    /// The migration's version, with any dashes removed.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// The migration's name (e.g. `create_users`).
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The SQL which applies this migration.
+    pub fn up(&self) -> &'a str {
+        self.up
+    }
+
+    /// The SQL which reverts this migration, if there is any.
+    pub fn down(&self) -> Option<&'a str> {
+        self.down
+    }
+
+    /// The SHA-256 hash of [`Migration::up()`], used to detect migrations
+    /// that were edited after being applied.
+    pub fn checksum(&self) -> [u8; 32] {
+        self.checksum
+    }
+}
+
+/// The error returned when a [`Dir`] doesn't contain valid migrations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationError {
+    path: PathBuf,
+    kind: MigrationErrorKind,
+}
+
+impl MigrationError {
+    fn new(path: impl Into<PathBuf>, kind: MigrationErrorKind) -> Self {
+        MigrationError {
+            path: path.into(),
+            kind,
+        }
+    }
+
+    /// The path of the offending file or directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What went wrong.
+    pub fn kind(&self) -> &MigrationErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = self.path.display();
+
+        match &self.kind {
+            MigrationErrorKind::InvalidName => {
+                write!(f, "\"{}\" isn't a valid migration name", path)
+            }
+            MigrationErrorKind::MissingUp => write!(f, "\"{}\" has no up migration", path),
+            MigrationErrorKind::DuplicateVersion(v) => {
+                write!(f, "\"{}\" reuses migration version {}", path, v)
+            }
+            MigrationErrorKind::Utf8(_) => write!(f, "\"{}\" isn't valid UTF-8", path),
+        }
+    }
+}
+
+impl Error for MigrationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            MigrationErrorKind::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The reasons a [`MigrationError`] may occur.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MigrationErrorKind {
+    /// The name doesn't follow the `V{version}__{name}.sql` or
+    /// `{version}_{name}/` layout.
+    InvalidName,
+    /// A directory has no `up.sql`, or there is an undo script without a
+    /// matching migration.
+    MissingUp,
+    /// More than one migration, or more than one undo script, has this
+    /// version.
+    DuplicateVersion(u64),
+    /// The SQL isn't valid UTF-8.
+    Utf8(std::str::Utf8Error),
+}
+
+#[cfg(feature = "rusqlite")]
+mod sqlite {
+    use super::{Migration, Migrations};
+    use rusqlite::{params, Connection, OptionalExtension};
+    use std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+    };
+
+    const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS include_dir_migrations (
+        version INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        checksum BLOB NOT NULL
+    )";
+
+    impl<'a> Migrations<'a> {
+        /// Apply any migrations which haven't been applied to a SQLite
+        /// database yet, returning the versions that were applied.
+        ///
+        /// Applied migrations are recorded in an `include_dir_migrations`
+        /// table. Each migration runs in its own transaction, and it is an
+        /// error if a previously applied migration has been edited or
+        /// removed.
+        pub fn apply(&self, conn: &mut Connection) -> Result<Vec<u64>, ApplyError> {
+            conn.execute(CREATE_TABLE, [])?;
+            self.check_applied(conn)?;
+
+            let mut applied = Vec::new();
+
+            for migration in self.iter() {
+                let exists = conn
+                    .query_row(
+                        "SELECT 1 FROM include_dir_migrations WHERE version = ?1",
+                        params![version_to_sql(migration.version)],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some();
+
+                if !exists {
+                    apply_one(conn, migration)?;
+                    applied.push(migration.version);
+                }
+            }
+
+            Ok(applied)
+        }
+
+        /// Revert every applied migration newer than `version` (newest
+        /// first), returning the versions that were reverted.
+        pub fn revert_to(
+            &self,
+            conn: &mut Connection,
+            version: u64,
+        ) -> Result<Vec<u64>, ApplyError> {
+            conn.execute(CREATE_TABLE, [])?;
+            self.check_applied(conn)?;
+
+            let mut reverted = Vec::new();
+
+            for migration in self
+                .migrations
+                .iter()
+                .rev()
+                .take_while(|m| m.version > version)
+            {
+                let tx = conn.transaction()?;
+                let deleted = tx.execute(
+                    "DELETE FROM include_dir_migrations WHERE version = ?1",
+                    params![version_to_sql(migration.version)],
+                )?;
+
+                if deleted == 0 {
+                    continue;
+                }
+
+                let down = migration.down.ok_or(ApplyError::Irreversible {
+                    version: migration.version,
+                })?;
+                tx.execute_batch(down)?;
+                tx.commit()?;
+                reverted.push(migration.version);
+            }
+
+            Ok(reverted)
+        }
+
+        /// Make sure every migration recorded in the database still exists
+        /// and hasn't changed.
+        fn check_applied(&self, conn: &Connection) -> Result<(), ApplyError> {
+            let mut stmt = conn.prepare("SELECT version, checksum FROM include_dir_migrations")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?;
+
+            for row in rows {
+                let (version, checksum) = row?;
+                let version = version as u64;
+
+                match self.get(version) {
+                    Some(m) if m.checksum[..] == checksum[..] => {}
+                    Some(_) => return Err(ApplyError::ChecksumMismatch { version }),
+                    None => return Err(ApplyError::UnknownVersion { version }),
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn apply_one(conn: &mut Connection, migration: &Migration<'_>) -> Result<(), ApplyError> {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.up)?;
+        tx.execute(
+            "INSERT INTO include_dir_migrations (version, name, checksum) VALUES (?1, ?2, ?3)",
+            params![
+                version_to_sql(migration.version),
+                migration.name,
+                &migration.checksum[..]
+            ],
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// SQLite only has signed integers, so versions are stored by reinterpreting
+    /// their bits.
+    fn version_to_sql(version: u64) -> i64 {
+        version as i64
+    }
+
+    /// The error returned when applying or reverting migrations fails.
+    #[derive(Debug)]
+    #[non_exhaustive]
+    pub enum ApplyError {
+        /// An applied migration's SQL was changed after it was applied.
+        ChecksumMismatch {
+            /// The migration's version.
+            version: u64,
+        },
+        /// The database contains a migration which no longer exists.
+        UnknownVersion {
+            /// The migration's version.
+            version: u64,
+        },
+        /// A migration needed to be reverted, but it has no down script.
+        Irreversible {
+            /// The migration's version.
+            version: u64,
+        },
+        /// The database returned an error.
+        Sqlite(rusqlite::Error),
+    }
+
+    impl From<rusqlite::Error> for ApplyError {
+        fn from(e: rusqlite::Error) -> Self {
+            ApplyError::Sqlite(e)
+        }
+    }
+
+    impl Display for ApplyError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            match self {
+                ApplyError::ChecksumMismatch { version } => write!(
+                    f,
+                    "Migration {} has been modified since it was applied",
+                    version
+                ),
+                ApplyError::UnknownVersion { version } => write!(
+                    f,
+                    "Migration {} has been applied but no longer exists",
+                    version
+                ),
+                ApplyError::Irreversible { version } => {
+                    write!(f, "Migration {} can't be reverted", version)
+                }
+                ApplyError::Sqlite(_) => write!(f, "Unable to apply migrations"),
+            }
+        }
+    }
+
+    impl Error for ApplyError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ApplyError::Sqlite(e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rusqlite")]
+pub use self::sqlite::ApplyError;
@@ -0,0 +1,328 @@
+use crate::{Dir, DirEntry};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A stack of embedded directories and directories on the real filesystem,
+/// where files in earlier layers take priority over files in later ones.
+///
+/// This is handy when an application ships default files using
+/// [`crate::include_dir!()`] but lets users override individual files.
+///
+/// ```rust
+/// use include_dir::{include_dir, Dir, Overlay};
+///
+/// static DEFAULTS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src");
+///
+/// let overlay = Overlay::new()
+///     .with_path("/etc/my-app/overrides")
+///     .with_dir(&DEFAULTS);
+///
+/// let lib_rs = overlay.get_file("lib.rs")?.unwrap();
+/// assert!(lib_rs.is_borrowed());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Overlay<'a> {
+    layers: Vec<Layer<'a>>,
+}
+
+#[derive(Debug, Clone)]
+enum Layer<'a> {
+    Embedded(&'a Dir<'a>),
+    Filesystem(PathBuf),
+}
+
+impl<'a> Overlay<'a> {
+    /// Create an empty [`Overlay`].
+    pub fn new() -> Self {
+        Overlay::default()
+    }
+
+    /// Add an embedded [`Dir`] with a lower priority than the existing layers.
+    ///
+    /// Paths are looked up relative to the [`Dir`] itself, so a sub-directory
+    /// like `ASSETS.get_dir("static")` can be used as a layer.
+    pub fn with_dir(mut self, dir: &'a Dir<'a>) -> Self {
+        self.layers.push(Layer::Embedded(dir));
+        self
+    }
+
+    /// Add a directory on the real filesystem with a lower priority than the
+    /// existing layers.
+    ///
+    /// The directory doesn't need to exist.
+    pub fn with_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.layers.push(Layer::Filesystem(path.into()));
+        self
+    }
+
+    /// Look up a file by name, returning it from the highest priority layer
+    /// that contains it.
+    ///
+    /// The path is normalized the same way as [`Dir::get_entry()`], so it is
+    /// never possible to read files outside a layer's root.
+    ///
+    /// A file which doesn't exist on disk (or is a directory) falls through
+    /// to the next layer, but any other error (e.g. permission denied) is
+    /// returned so a broken override doesn't silently look like a missing
+    /// one.
+    pub fn get_file<S: AsRef<Path>>(&self, path: S) -> io::Result<Option<OverlayFile<'a>>> {
+        let path = match crate::paths::normalize(path.as_ref()) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        for layer in &self.layers {
+            let file =
+                match layer {
+                    Layer::Embedded(dir) => get_embedded(dir, &path)
+                        .and_then(DirEntry::as_file)
+                        .map(|f| OverlayFile {
+                            path: PathBuf::from(&path),
+                            contents: Cow::Borrowed(f.contents()),
+                        }),
+                    Layer::Filesystem(root) => read_file(root, &path)?,
+                };
+
+            if file.is_some() {
+                return Ok(file);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Does any layer contain `path`?
+    ///
+    /// Like [`Overlay::get_file()`], errors other than the path not existing
+    /// are returned.
+    pub fn contains<S: AsRef<Path>>(&self, path: S) -> io::Result<bool> {
+        let path = match crate::paths::normalize(path.as_ref()) {
+            Some(p) => p,
+            None => return Ok(false),
+        };
+
+        for layer in &self.layers {
+            let found = match layer {
+                Layer::Embedded(dir) => get_embedded(dir, &path).is_some(),
+                Layer::Filesystem(root) => match fs::metadata(root.join(&path)) {
+                    Ok(_) => true,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => false,
+                    Err(e) => return Err(e),
+                },
+            };
+
+            if found {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// The entries at the top level of the overlay, sorted by path.
+    ///
+    /// Directories on disk which can't be read are skipped rather than
+    /// treated as errors, so one unreadable layer doesn't hide the others.
+    pub fn entries(&self) -> Vec<OverlayEntry> {
+        let mut entries = BTreeMap::new();
+
+        for layer in &self.layers {
+            match layer {
+                Layer::Embedded(dir) => {
+                    for entry in dir.entries() {
+                        let entry = relative_entry(dir, entry);
+                        entries.entry(entry.path().to_path_buf()).or_insert(entry);
+                    }
+                }
+                Layer::Filesystem(root) => read_dir(root, root, false, &mut entries),
+            }
+        }
+
+        entries.into_values().collect()
+    }
+
+    /// Recursively iterate over every entry in the overlay, sorted by path.
+    ///
+    /// Like [`Overlay::entries()`], directories on disk which can't be read
+    /// are skipped.
+    pub fn walk(&self) -> impl Iterator<Item = OverlayEntry> {
+        let mut entries = BTreeMap::new();
+
+        for layer in &self.layers {
+            match layer {
+                Layer::Embedded(dir) => {
+                    for entry in dir.walk() {
+                        let entry = relative_entry(dir, entry);
+                        entries.entry(entry.path().to_path_buf()).or_insert(entry);
+                    }
+                }
+                Layer::Filesystem(root) => read_dir(root, root, true, &mut entries),
+            }
+        }
+
+        entries.into_values()
+    }
+}
+
+/// Look up an entry in an embedded layer, where `path` has already been
+/// normalized and is relative to the layer's own path.
+pub(crate) fn get_embedded<'a>(dir: &'a Dir<'a>, path: &str) -> Option<&'a DirEntry<'a>> {
+    match dir.path().to_str()? {
+        "" => dir.get_entry(path),
+        parent => dir.get_entry(format!("{}/{}", parent, path)),
+    }
+}
+
+/// Convert an entry from an embedded layer to one whose path is relative to
+/// the layer.
+pub(crate) fn relative_entry(dir: &Dir<'_>, entry: &DirEntry<'_>) -> OverlayEntry {
+    let path = entry
+        .path()
+        .strip_prefix(dir.path())
+        .unwrap_or_else(|_| entry.path())
+        .to_path_buf();
+
+    match entry {
+        DirEntry::Dir(_) => OverlayEntry::Dir(path),
+        DirEntry::File(_) => OverlayEntry::File(path),
+    }
+}
+
+/// Read a file relative to a directory on disk, where `path` has already been
+/// normalized.
+///
+/// Returns `Ok(None)` if the file doesn't exist or is a directory.
+pub(crate) fn read_file(root: &Path, path: &str) -> io::Result<Option<OverlayFile<'static>>> {
+    let full_path = root.join(path);
+
+    match fs::read(&full_path) {
+        Ok(contents) => Ok(Some(OverlayFile {
+            path: PathBuf::from(path),
+            contents: Cow::Owned(contents),
+        })),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(_) if full_path.is_dir() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Add the contents of a directory on disk to `entries`, unless a higher
+/// priority layer already provided them.
+///
+/// Listing is best-effort, so errors are deliberately ignored: a layer which
+/// doesn't exist or can't be read contributes nothing rather than hiding the
+/// other layers, and entries whose names aren't valid UTF-8 are skipped
+/// because they could never be looked up. [`Overlay::get_file()`] reports
+/// why a particular file can't be read.
+pub(crate) fn read_dir(
+    root: &Path,
+    dir: &Path,
+    recursive: bool,
+    entries: &mut BTreeMap<PathBuf, OverlayEntry>,
+) {
+    let children = match dir.read_dir() {
+        Ok(children) => children,
+        Err(_) => return,
+    };
+
+    for child in children.filter_map(Result::ok) {
+        let full_path = child.path();
+        let path = match full_path.strip_prefix(root).ok().and_then(Path::to_str) {
+            Some(path) => PathBuf::from(path.replace('\\', "/")),
+            None => continue,
+        };
+
+        let entry = if full_path.is_dir() {
+            if recursive {
+                read_dir(root, &full_path, recursive, entries);
+            }
+            OverlayEntry::Dir(path.clone())
+        } else {
+            OverlayEntry::File(path.clone())
+        };
+
+        entries.entry(path).or_insert(entry);
+    }
+}
+
+/// A file retrieved from an [`Overlay`] or another [`crate::AssetSource`],
+/// which either borrows from an embedded [`Dir`] or owns contents read from
+/// disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayFile<'a> {
+    path: PathBuf,
+    contents: Cow<'a, [u8]>,
+}
+
+impl<'a> OverlayFile<'a> {
+    pub(crate) fn new(path: PathBuf, contents: Cow<'a, [u8]>) -> Self {
+        OverlayFile { path, contents }
+    }
+
+    /// The file's path, relative to the root of the [`Overlay`].
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file's raw contents.
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+
+    /// The file's contents interpreted as a string.
+    pub fn contents_utf8(&self) -> Option<&str> {
+        std::str::from_utf8(self.contents()).ok()
+    }
+
+    /// Was this file borrowed from an embedded [`Dir`]?
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.contents, Cow::Borrowed(_))
+    }
+
+    /// Take ownership of the file's contents.
+    pub fn into_contents(self) -> Cow<'a, [u8]> {
+        self.contents
+    }
+}
+
+/// An entry in an [`Overlay`] or another [`crate::AssetSource`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OverlayEntry {
+    /// A directory.
+    Dir(PathBuf),
+    /// A file, which can be read using [`Overlay::get_file()`].
+    File(PathBuf),
+}
+
+impl OverlayEntry {
+    /// The entry's path, relative to the root of the [`Overlay`].
+    pub fn path(&self) -> &Path {
+        match self {
+            OverlayEntry::Dir(path) | OverlayEntry::File(path) => path,
+        }
+    }
+
+    /// Is this entry a directory?
+    pub fn is_dir(&self) -> bool {
+        matches!(self, OverlayEntry::Dir(_))
+    }
+
+    /// Is this entry a file?
+    pub fn is_file(&self) -> bool {
+        matches!(self, OverlayEntry::File(_))
+    }
+}
+
+impl<'a> From<&DirEntry<'a>> for OverlayEntry {
+    fn from(entry: &DirEntry<'a>) -> Self {
+        match entry {
+            DirEntry::Dir(d) => OverlayEntry::Dir(d.path().to_path_buf()),
+            DirEntry::File(f) => OverlayEntry::File(f.path().to_path_buf()),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+/// Normalize a lookup path so it can be compared against the paths embedded by
+/// [`crate::include_dir!()`].
+///
+/// Both `/` and `\` are accepted as separators, leading separators and `.`
+/// components are dropped, and `..` removes the previous component. A `..`
+/// that would escape the root is ignored, so the result always refers to
+/// something inside the embedded directory.
+///
+/// Returns `None` if the path isn't valid UTF-8 or refers to the root itself.
+pub(crate) fn normalize(path: &std::path::Path) -> Option<String> {
+    let path = path.to_str()?;
+    let mut components = Vec::new();
+
+    for component in path.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            other => components.push(other),
+        }
+    }
+
+    if components.is_empty() {
+        return None;
+    }
+
+    Some(components.join("/"))
+}
+
+/// Normalize `path` and pass it to `find`.
+///
+/// When the `nfc` feature is enabled and nothing was found, `find` is called
+/// again with the path converted to Unicode Normalization Form C, so lookups
+/// work whether or not the directory was embedded using
+/// `normalize_unicode = true`.
+pub(crate) fn lookup<T>(
+    path: &std::path::Path,
+    mut find: impl FnMut(&str) -> Option<T>,
+) -> Option<T> {
+    let path = normalize(path)?;
+
+    if let Some(found) = find(&path) {
+        return Some(found);
+    }
+
+    #[cfg(feature = "nfc")]
+    {
+        let nfc: String = unicode_normalization::UnicodeNormalization::nfc(path.as_str()).collect();
+        if nfc != path {
+            return find(&nfc);
+        }
+    }
+
+    None
+}
+
+/// Map a path to a form where comparisons ignore differences in case.
+///
+/// This uses the Unicode lowercase mapping, so `É` and `é` compare equal.
+pub(crate) fn fold_case(path: &str) -> String {
+    path.to_lowercase()
+}
@@ -0,0 +1,177 @@
+use crate::{Dir, File};
+use std::path::Path;
+
+/// Registers the templates embedded in a [`Dir`] with a template engine.
+///
+/// Templates are named after their path relative to the [`Dir`], using `/`
+/// as a separator, so nested directories are preserved and `{% extends %}`,
+/// `{% include %}` and partials can refer to each other the same way they
+/// would when loading templates from disk.
+///
+/// ```rust
+/// # #[cfg(feature = "tera")] {
+/// use include_dir::{include_dir, Dir, Templates};
+///
+/// static TEMPLATES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures/templates");
+///
+/// let mut tera = tera::Tera::default();
+/// Templates::new(&TEMPLATES)
+///     .with_extension("tera")
+///     .register_tera(&mut tera)
+///     .unwrap();
+///
+/// assert!(tera.get_template_names().any(|name| name == "pages/index.tera"));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Templates<'a> {
+    dir: &'a Dir<'a>,
+    extensions: Vec<String>,
+}
+
+impl<'a> Templates<'a> {
+    /// Use every file in `dir` (and its sub-directories) as a template.
+    pub fn new(dir: &'a Dir<'a>) -> Self {
+        Templates {
+            dir,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Only use files with this extension as templates.
+    ///
+    /// This can be called several times to accept multiple extensions. If it
+    /// is never called, every file is treated as a template.
+    pub fn with_extension<S: Into<String>>(mut self, extension: S) -> Self {
+        let extension = extension.into();
+        self.extensions
+            .push(extension.trim_start_matches('.').to_string());
+        self
+    }
+
+    /// Iterate over the templates, yielding each template's name (its path
+    /// relative to the [`Dir`]) and the file it came from.
+    pub fn iter(&self) -> impl Iterator<Item = (String, &'a File<'a>)> + '_ {
+        self.dir
+            .walk()
+            .filter_map(crate::DirEntry::as_file)
+            .filter(move |f| self.accepts(f.path()))
+            .filter_map(move |f| Some((self.name_of(f.path())?, f)))
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        if self.extensions.is_empty() {
+            return true;
+        }
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) => self.extensions.iter().any(|e| e == ext),
+            None => false,
+        }
+    }
+
+    /// The name a template is registered under.
+    fn name_of(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(self.dir.path()).ok()?;
+        relative.to_str().map(|s| s.replace('\\', "/"))
+    }
+
+    /// Look up a template by the name it would be registered under.
+    pub fn get(&self, name: &str) -> Option<&'a File<'a>> {
+        let file =
+            crate::paths::lookup(Path::new(name), |path| match self.dir.path().to_str()? {
+                "" => self.dir.get_file_strict(path),
+                parent => self.dir.get_file_strict(format!("{}/{}", parent, path)),
+            })?;
+
+        if self.accepts(file.path()) {
+            Some(file)
+        } else {
+            None
+        }
+    }
+
+    /// Add every template to a [`tera::Tera`] instance.
+    ///
+    /// The templates are added in one go, so inheritance chains between them
+    /// are resolved regardless of the order they are stored in.
+    #[cfg(feature = "tera")]
+    pub fn register_tera(&self, tera: &mut tera::Tera) -> tera::Result<()> {
+        let mut templates = Vec::new();
+
+        for (name, file) in self.iter() {
+            let source = file.contents_utf8().ok_or_else(|| {
+                tera::Error::msg(format!("The template \"{}\" isn't valid UTF-8", name))
+            })?;
+            templates.push((name, source));
+        }
+
+        tera.add_raw_templates(templates)
+    }
+
+    /// Add every template to a [`handlebars::Handlebars`] registry.
+    ///
+    /// Following the convention used by
+    /// `Handlebars::register_templates_directory()`, templates are registered
+    /// without their extension, so `partials/header.hbs` is available as
+    /// `{{> partials/header}}`.
+    #[cfg(feature = "handlebars")]
+    pub fn register_handlebars(
+        &self,
+        registry: &mut handlebars::Handlebars<'_>,
+    ) -> Result<(), handlebars::TemplateError> {
+        for (name, file) in self.iter() {
+            let name = match Path::new(&name).extension() {
+                Some(ext) => &name[..name.len() - ext.len() - 1],
+                None => name.as_str(),
+            };
+
+            let source = file.contents_utf8().ok_or_else(|| {
+                let e = std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid UTF-8");
+                handlebars::TemplateError::of(handlebars::TemplateErrorReason::IoError(
+                    e,
+                    name.to_string(),
+                ))
+            })?;
+
+            registry.register_template_string(name, source)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "minijinja")]
+impl Templates<'static> {
+    /// Create a callback for [`minijinja::Environment::set_loader()`] which
+    /// loads templates on demand by looking them up with [`Templates::get()`].
+    ///
+    /// ```rust
+    /// use include_dir::{include_dir, Dir, Templates};
+    ///
+    /// static TEMPLATES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures/templates");
+    ///
+    /// let mut env = minijinja::Environment::new();
+    /// env.set_loader(Templates::new(&TEMPLATES).minijinja_loader());
+    ///
+    /// let greeting = env.get_template("greeting.jinja").unwrap();
+    /// assert_eq!(greeting.render(minijinja::context! { name => "World" }).unwrap(), "Hello, World!");
+    /// ```
+    pub fn minijinja_loader(
+        self,
+    ) -> impl Fn(&str) -> Result<Option<String>, minijinja::Error> + Send + Sync + 'static {
+        move |name| match self.get(name) {
+            Some(file) => match file.contents_utf8() {
+                Some(source) => Ok(Some(source.to_string())),
+                None => Err(minijinja::Error::new(
+                    minijinja::ErrorKind::InvalidOperation,
+                    format!(
+                        "The template file \"{}\" isn't valid UTF-8",
+                        file.path().display()
+                    ),
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+}
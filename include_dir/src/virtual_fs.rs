@@ -0,0 +1,100 @@
+use crate::{Dir, DirEntry};
+use std::io::{Cursor, Write};
+use vfs::{
+    error::VfsErrorKind, FileSystem, SeekAndRead, VfsError, VfsFileType, VfsMetadata, VfsResult,
+};
+
+/// A read-only [`FileSystem`] backed by an embedded [`Dir`].
+///
+/// Any attempt to modify the filesystem will fail with
+/// [`VfsErrorKind::NotSupported`].
+impl FileSystem for Dir<'static> {
+    fn read_dir(&self, path: &str) -> VfsResult<Box<dyn Iterator<Item = String> + Send>> {
+        let entries = match lookup(self, path)? {
+            Some(DirEntry::Dir(d)) => d.entries(),
+            Some(DirEntry::File(_)) => {
+                return Err(VfsErrorKind::Other("Not a directory".into()).into())
+            }
+            None => self.entries(),
+        };
+
+        let names = entries.iter().filter_map(|entry| {
+            entry
+                .path()
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(String::from)
+        });
+
+        Ok(Box::new(names))
+    }
+
+    fn create_dir(&self, _path: &str) -> VfsResult<()> {
+        Err(read_only())
+    }
+
+    fn open_file(&self, path: &str) -> VfsResult<Box<dyn SeekAndRead + Send>> {
+        match lookup(self, path)? {
+            Some(DirEntry::File(f)) => Ok(Box::new(Cursor::new(f.contents()))),
+            Some(DirEntry::Dir(_)) | None => Err(VfsErrorKind::FileNotFound.into()),
+        }
+    }
+
+    fn create_file(&self, _path: &str) -> VfsResult<Box<dyn Write + Send>> {
+        Err(read_only())
+    }
+
+    fn append_file(&self, _path: &str) -> VfsResult<Box<dyn Write + Send>> {
+        Err(read_only())
+    }
+
+    fn metadata(&self, path: &str) -> VfsResult<VfsMetadata> {
+        let metadata = match lookup(self, path)? {
+            Some(DirEntry::File(f)) => VfsMetadata {
+                file_type: VfsFileType::File,
+                len: f.contents().len() as u64,
+            },
+            Some(DirEntry::Dir(_)) | None => VfsMetadata {
+                file_type: VfsFileType::Directory,
+                len: 0,
+            },
+        };
+
+        Ok(metadata)
+    }
+
+    fn exists(&self, path: &str) -> VfsResult<bool> {
+        match lookup(self, path) {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.kind(), VfsErrorKind::FileNotFound) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn remove_file(&self, _path: &str) -> VfsResult<()> {
+        Err(read_only())
+    }
+
+    fn remove_dir(&self, _path: &str) -> VfsResult<()> {
+        Err(read_only())
+    }
+}
+
+/// Find the entry at a `vfs` path (e.g. `"/src/lib.rs"`), where `None` means
+/// the root directory itself.
+fn lookup(dir: &Dir<'static>, path: &str) -> VfsResult<Option<&'static DirEntry<'static>>> {
+    let path = path.trim_start_matches('/');
+
+    if path.is_empty() {
+        return Ok(None);
+    }
+
+    match dir.get_entry(path) {
+        Some(entry) => Ok(Some(entry)),
+        None => Err(VfsErrorKind::FileNotFound.into()),
+    }
+}
+
+fn read_only() -> VfsError {
+    VfsError::from(VfsErrorKind::NotSupported).with_context(|| "Embedded directories are read-only")
+}
@@ -0,0 +1,85 @@
+use include_dir::{include_dir, include_file, Dir, File};
+
+static TREE: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures", align = 64);
+static DATA_ONLY: Dir<'_> = include_dir!(
+    "$CARGO_MANIFEST_DIR/tests/fixtures",
+    align = { "data/*" => 16, "data/*.json" => 32 },
+);
+static PLAIN: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures");
+static CARGO_TOML: File<'_> = include_file!("$CARGO_MANIFEST_DIR/Cargo.toml", align = 8);
+
+fn files<'a>(dir: &'a Dir<'a>) -> impl Iterator<Item = &'a File<'a>> {
+    dir.walk().filter_map(|e| e.as_file())
+}
+
+#[test]
+fn every_file_is_aligned() {
+    for file in files(&TREE) {
+        assert!(file.is_aligned_to(64), "{}", file.path().display());
+    }
+
+    assert!(CARGO_TOML.is_aligned_to(8));
+}
+
+#[test]
+fn only_matching_files_are_aligned() {
+    for file in files(&DATA_ONLY) {
+        match file.path().to_str().unwrap() {
+            "data/server.json" => assert!(file.is_aligned_to(32)),
+            path if path.starts_with("data/") => assert!(file.is_aligned_to(16), "{}", path),
+            _ => {}
+        }
+    }
+
+    assert!(DATA_ONLY.get_file("text/windows.ini").unwrap().is_text());
+}
+
+#[test]
+#[should_panic(expected = "Alignment must be a power of two, not 0")]
+fn zero_alignment_panics() {
+    CARGO_TOML.is_aligned_to(0);
+}
+
+#[test]
+#[should_panic(expected = "Alignment must be a power of two, not 24")]
+fn alignment_must_be_a_power_of_two() {
+    CARGO_TOML.is_aligned_to(24);
+}
+
+#[test]
+fn aligned_files_have_the_same_contents() {
+    assert_eq!(TREE, PLAIN);
+    assert_eq!(DATA_ONLY, PLAIN);
+}
+
+#[test]
+fn aligned_files_are_embedded_as_bytes() {
+    assert!(files(&TREE).all(|f| !f.is_text()));
+
+    let server = TREE.get_file("data/server.json").unwrap();
+    assert!(server.as_str().is_none());
+    assert!(server.contents_utf8().is_some());
+}
+
+#[test]
+#[cfg(feature = "bytemuck")]
+fn read_contents_in_place() {
+    for file in files(&TREE) {
+        let contents = file.contents();
+
+        match file.contents_as::<u32>() {
+            Ok(words) => {
+                let expected: Vec<u32> = contents
+                    .chunks(4)
+                    .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()))
+                    .collect();
+                assert_eq!(words, expected);
+                assert_eq!(words.as_ptr() as *const u8, contents.as_ptr());
+            }
+            Err(e) => {
+                assert_ne!(contents.len() % 4, 0, "{}: {:?}", file.path().display(), e);
+                assert_eq!(e, bytemuck::PodCastError::OutputSliceWouldHaveSlop);
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use include_dir::{
+    include_archive, include_dir, ArchiveDir, ArchiveEntry, ArchiveError, Dir, DirEntry, File,
+};
+
+static SRC: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src");
+static FIXTURES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures");
+static FIXTURES_ARCHIVE: &[u8] = include_archive!("$CARGO_MANIFEST_DIR/tests/fixtures");
+
+#[test]
+fn round_trip_a_dir() {
+    let bytes = FIXTURES.to_archive_bytes();
+    let archive = ArchiveDir::from_bytes(&bytes).unwrap();
+
+    let original: Vec<_> = FIXTURES.walk().map(|e| e.path()).collect();
+    let archived: Vec<_> = archive.walk().map(|e| e.path()).collect();
+    assert_eq!(archived, original);
+
+    for file in FIXTURES.walk().filter_map(|e| e.as_file()) {
+        let archived = archive.get_file(file.path()).unwrap();
+        assert_eq!(archived.contents(), file.contents());
+        assert!(archived.hash().is_some());
+        assert!(archived.verify());
+    }
+}
+
+#[test]
+fn the_macro_and_runtime_produce_the_same_archive() {
+    assert_eq!(FIXTURES_ARCHIVE, FIXTURES.to_archive_bytes());
+}
+
+#[test]
+fn look_up_entries() {
+    let archive = ArchiveDir::from_bytes(FIXTURES_ARCHIVE).unwrap();
+
+    let top_level: Vec<_> = archive.entries().map(|e| e.path()).collect();
+    let expected: Vec<_> = FIXTURES.entries().iter().map(|e| e.path()).collect();
+    assert_eq!(top_level, expected);
+
+    let data = archive.get_dir("data").unwrap();
+    assert_eq!(data.path().to_str(), Some("data"));
+    assert!(data
+        .files()
+        .any(|f| f.path().to_str() == Some("data/server.json")));
+
+    let file = archive.get_file("./data/../data/server.json").unwrap();
+    assert_eq!(
+        file.contents(),
+        FIXTURES.get_file("data/server.json").unwrap().contents()
+    );
+    assert!(archive.get_file("data").is_none());
+    assert!(archive.get_dir("data/server.json").is_none());
+    assert!(!archive.contains("data/missing.json"));
+}
+
+#[test]
+fn empty_directories() {
+    static EMPTY: Dir<'_> = Dir::new("", &[]);
+
+    let bytes = EMPTY.to_archive_bytes();
+    let archive = ArchiveDir::from_bytes(&bytes).unwrap();
+
+    assert_eq!(archive.entries().count(), 0);
+}
+
+#[test]
+#[cfg(feature = "glob")]
+fn find_with_a_glob() {
+    let bytes = SRC.to_archive_bytes();
+    let archive = ArchiveDir::from_bytes(&bytes).unwrap();
+
+    let found: Vec<_> = archive.find("*.rs").unwrap().map(|e| e.path()).collect();
+    let expected: Vec<_> = SRC.find("*.rs").unwrap().map(|e| e.path()).collect();
+
+    assert!(found.iter().any(|p| p.to_str() == Some("lib.rs")));
+    assert_eq!(found.len(), expected.len());
+}
+
+#[test]
+fn reject_invalid_archives() {
+    let bytes = SRC.to_archive_bytes();
+
+    assert_eq!(
+        ArchiveDir::from_bytes(b"not an archive").unwrap_err(),
+        ArchiveError::InvalidMagic
+    );
+
+    let mut future_version = bytes.clone();
+    future_version[4] = 2;
+    assert_eq!(
+        ArchiveDir::from_bytes(&future_version).unwrap_err(),
+        ArchiveError::UnsupportedVersion(2)
+    );
+
+    let truncated = &bytes[..bytes.len() - 1];
+    assert_eq!(
+        ArchiveDir::from_bytes(truncated).unwrap_err(),
+        ArchiveError::Corrupted
+    );
+}
+
+#[test]
+fn directories_cant_share_children() {
+    static A: &[DirEntry<'_>] = &[DirEntry::File(File::new("a/x.txt", b"x"))];
+    static B: &[DirEntry<'_>] = &[DirEntry::File(File::new("b/y.txt", b"y"))];
+    static ROOT: &[DirEntry<'_>] = &[
+        DirEntry::Dir(Dir::new("a", A)),
+        DirEntry::Dir(Dir::new("b", B)),
+    ];
+    let bytes = Dir::new("", ROOT).to_archive_bytes();
+    assert!(ArchiveDir::from_bytes(&bytes).is_ok());
+
+    // point "b" at "a"'s children, so "a/x.txt" has two parents. Repeating
+    // that on every level would make walking the archive exponential.
+    let mut shared = bytes.clone();
+    let b_children = 16 + 40 + 16;
+    shared[b_children..b_children + 8].copy_from_slice(&2_u64.to_le_bytes());
+    assert_eq!(
+        ArchiveDir::from_bytes(&shared).unwrap_err(),
+        ArchiveError::Corrupted
+    );
+
+    // or leave "b/y.txt" without a parent
+    let mut orphaned = bytes;
+    orphaned[b_children + 8..b_children + 16].copy_from_slice(&0_u64.to_le_bytes());
+    assert_eq!(
+        ArchiveDir::from_bytes(&orphaned).unwrap_err(),
+        ArchiveError::Corrupted
+    );
+}
+
+#[test]
+fn corrupted_archives_never_panic() {
+    let bytes = SRC.to_archive_bytes();
+    let table_len = 16 + SRC.walk().count() * 40;
+
+    for i in 0..table_len {
+        for value in [0x00, 0x01, 0x7f, 0xff] {
+            let mut corrupted = bytes.clone();
+            corrupted[i] = value;
+
+            if let Ok(archive) = ArchiveDir::from_bytes(&corrupted) {
+                for entry in archive.walk() {
+                    if let ArchiveEntry::File(f) = entry {
+                        let _ = f.contents();
+                        let _ = archive.get_entry(f.path());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "metadata")]
+fn metadata_is_preserved() {
+    static FIXTURES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures", metadata = true);
+    static FIXTURES_ARCHIVE: &[u8] =
+        include_archive!("$CARGO_MANIFEST_DIR/tests/fixtures", metadata = true);
+
+    let archive = ArchiveDir::from_bytes(FIXTURES_ARCHIVE).unwrap();
+    let file = archive.get_file("data/server.json").unwrap();
+    let original = FIXTURES.get_file("data/server.json").unwrap();
+
+    let archived = file.metadata().unwrap();
+    let expected = original.metadata().unwrap();
+    assert_eq!(archived.modified(), expected.modified());
+    assert_eq!(archived.mode(), expected.mode());
+    assert_eq!(archived.len(), expected.len());
+
+    let bytes = FIXTURES.to_archive_bytes();
+    let round_tripped = ArchiveDir::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        round_tripped
+            .get_file("data/server.json")
+            .unwrap()
+            .metadata(),
+        Some(expected)
+    );
+
+    // directories have metadata too
+    let data = round_tripped.get_dir("data").unwrap();
+    assert_eq!(
+        data.metadata(),
+        FIXTURES.get_dir("data").unwrap().metadata()
+    );
+    assert!(data.metadata().is_some());
+}
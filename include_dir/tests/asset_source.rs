@@ -0,0 +1,103 @@
+use include_dir::{
+    include_archive, include_dir, ArchiveDir, AssetSource, Dir, DirBuf, Overlay, OverlayEntry,
+};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+static TESTS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures");
+static ARCHIVE: &[u8] = include_archive!("$CARGO_MANIFEST_DIR/tests/fixtures");
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+/// Code which only knows about the trait.
+fn summarize(source: impl AssetSource) -> (Vec<PathBuf>, Vec<PathBuf>, Option<Vec<u8>>) {
+    let top_level = source
+        .entries()
+        .iter()
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    let files = source
+        .walk()
+        .filter(OverlayEntry::is_file)
+        .map(|e| e.path().to_path_buf())
+        .collect();
+    let contents = source
+        .get_file(Path::new("./data/server.json"))
+        .map(|f| f.contents().to_vec());
+
+    (top_level, files, contents)
+}
+
+#[test]
+fn every_source_sees_the_same_files() {
+    let expected = summarize(&TESTS);
+    assert!(expected.2.is_some());
+
+    let owned = DirBuf::from(&TESTS);
+    assert_eq!(summarize(&owned), expected);
+
+    let archive = ArchiveDir::from_bytes(ARCHIVE).unwrap();
+    assert_eq!(summarize(archive), expected);
+
+    assert_eq!(summarize(fixtures()), expected);
+    assert_eq!(summarize(fixtures().as_path()), expected);
+
+    let overlay = Overlay::new().with_dir(&TESTS);
+    assert_eq!(summarize(&overlay), expected);
+
+    let boxed: Box<dyn AssetSource> = Box::new(owned);
+    assert_eq!(summarize(boxed), expected);
+}
+
+#[test]
+fn contains() {
+    let sources: Vec<Box<dyn AssetSource>> = vec![
+        Box::new(&TESTS),
+        Box::new(DirBuf::from(&TESTS)),
+        Box::new(ArchiveDir::from_bytes(ARCHIVE).unwrap()),
+        Box::new(fixtures()),
+        Box::new(Overlay::new().with_path(fixtures())),
+    ];
+
+    for source in &sources {
+        assert!(source.contains(Path::new("data")));
+        assert!(source.contains(Path::new("data/../data/server.json")));
+        assert!(!source.contains(Path::new("missing.txt")));
+        assert!(!source.contains(Path::new("")));
+    }
+}
+
+#[test]
+fn filesystem_paths_cant_escape_the_root() {
+    let tmpdir = TempDir::new().unwrap();
+    std::fs::create_dir(tmpdir.path().join("root")).unwrap();
+    std::fs::write(tmpdir.path().join("secret.txt"), "secret").unwrap();
+    let root = tmpdir.path().join("root");
+
+    assert!(root.get_file(Path::new("../secret.txt")).is_none());
+    assert!(root.entries().is_empty());
+}
+
+#[test]
+fn sub_directories_are_their_own_root() {
+    let data = TESTS.get_dir("data").unwrap();
+    let overlay = Overlay::new().with_dir(data);
+
+    let file = AssetSource::get_file(data, Path::new("server.json")).unwrap();
+    assert_eq!(file.path(), Path::new("server.json"));
+    assert_eq!(file.contents(), include_bytes!("fixtures/data/server.json"));
+    assert!(AssetSource::get_file(data, Path::new("data/server.json")).is_none());
+    assert!(AssetSource::contains(data, Path::new("server.json")));
+    assert!(!AssetSource::contains(data, Path::new("")));
+
+    assert_eq!(AssetSource::entries(data), AssetSource::entries(&overlay));
+    assert_eq!(
+        AssetSource::walk(data).collect::<Vec<_>>(),
+        AssetSource::walk(&overlay).collect::<Vec<_>>()
+    );
+    assert!(AssetSource::entries(data).contains(&OverlayEntry::File(PathBuf::from("server.json"))));
+}
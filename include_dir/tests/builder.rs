@@ -0,0 +1,141 @@
+#![cfg(feature = "build")]
+
+use include_dir::Builder;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/data"))
+}
+
+#[test]
+fn copy_a_directory_unchanged() {
+    let temp = tempfile::tempdir().unwrap();
+    let dest = temp.path().join("data");
+
+    Builder::new(fixtures()).build(&dest).unwrap();
+
+    for name in ["server.json", "proxy.toml", "client.yaml"] {
+        assert_eq!(
+            fs::read(dest.join(name)).unwrap(),
+            fs::read(fixtures().join(name)).unwrap()
+        );
+    }
+}
+
+#[test]
+fn transform_and_rename_matching_files() {
+    let temp = tempfile::tempdir().unwrap();
+    let dest = temp.path().join("data");
+
+    Builder::new(fixtures())
+        .transform("*.json", |path, contents| {
+            assert_eq!(path, Path::new("server.json"));
+            contents.to_ascii_uppercase()
+        })
+        .rename("*.toml", |path| path.with_extension("ini"))
+        .transform("*.ini", |_, _| b"renamed".to_vec())
+        .build(&dest)
+        .unwrap();
+
+    let json = fs::read(fixtures().join("server.json")).unwrap();
+    assert_eq!(
+        fs::read(dest.join("server.json")).unwrap(),
+        json.to_ascii_uppercase()
+    );
+    assert_eq!(fs::read(dest.join("proxy.ini")).unwrap(), b"renamed");
+    assert!(!dest.join("proxy.toml").exists());
+}
+
+#[test]
+fn stale_files_are_removed() {
+    let temp = tempfile::tempdir().unwrap();
+    let dest = temp.path().join("data");
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(dest.join("deleted.txt"), "stale").unwrap();
+
+    Builder::new(fixtures()).build(&dest).unwrap();
+
+    assert!(!dest.join("deleted.txt").exists());
+    assert!(dest.join("server.json").exists());
+}
+
+#[test]
+fn renaming_two_files_to_the_same_path_is_an_error() {
+    let temp = tempfile::tempdir().unwrap();
+
+    let err = Builder::new(fixtures())
+        .rename("*", |_| "config".into())
+        .build(temp.path().join("data"))
+        .unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+}
+
+#[test]
+fn renaming_outside_the_destination_is_an_error() {
+    let temp = tempfile::tempdir().unwrap();
+    let dest = temp.path().join("data");
+    let escaped = temp.path().join("escaped.json");
+
+    for target in [
+        Path::new("../escaped.json").to_path_buf(),
+        Path::new("nested/../../escaped.json").to_path_buf(),
+        escaped.clone(),
+        PathBuf::new(),
+    ] {
+        let err = Builder::new(fixtures())
+            .rename("*.json", move |_| target.clone())
+            .build(&dest)
+            .unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!escaped.exists());
+    }
+
+    Builder::new(fixtures())
+        .rename("*.json", |path| Path::new("./json").join(path))
+        .build(&dest)
+        .unwrap();
+    assert!(dest.join("json/server.json").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn symlinked_directories_are_followed() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    std::os::unix::fs::symlink(fixtures(), source.join("linked")).unwrap();
+    let dest = temp.path().join("dest");
+
+    Builder::new(&source).build(&dest).unwrap();
+
+    assert_eq!(
+        fs::read(dest.join("linked/server.json")).unwrap(),
+        fs::read(fixtures().join("server.json")).unwrap()
+    );
+}
+
+#[test]
+fn building_over_the_source_is_an_error() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("assets");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("index.html"), "<h1>Hello</h1>").unwrap();
+
+    for dest in [source.clone(), temp.path().to_path_buf(), source.join("..")] {
+        let err = Builder::new(&source).build(&dest).unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(source.join("index.html").exists());
+    }
+}
+
+#[test]
+#[should_panic(expected = "isn't a valid glob pattern")]
+fn invalid_globs_are_rejected() {
+    let _ = Builder::new(fixtures()).transform("[", |_, c| c);
+}
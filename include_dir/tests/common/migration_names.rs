@@ -0,0 +1,46 @@
+// Migration names and layouts and how they should be parsed, shared by the
+// tests for `Dir::migrations()` and the macro's `validate_migrations` option
+// so the two implementations can't drift apart.
+
+/// `.sql` file names, with the kind (`V` or `U`) and version they parse to.
+/// Every other `.sql` file is an invalid migration name.
+pub(crate) const FILE_NAMES: &[(&str, Option<(char, u64)>)] = &[
+    ("V1__create_users.sql", Some(('V', 1))),
+    ("V01__create_users.sql", Some(('V', 1))),
+    ("U12__create_users.sql", Some(('U', 12))),
+    ("V1__add_index__users.sql", Some(('V', 1))),
+    ("V1_create_users.sql", None),
+    ("V__create_users.sql", None),
+    ("V1__.sql", None),
+    ("Vx__create_users.sql", None),
+    ("create_users.sql", None),
+    ("v1__create_users.sql", None),
+];
+
+/// Names of directories starting with a digit, with the version they parse
+/// to. Directories which don't start with a digit are ignored.
+pub(crate) const DIR_NAMES: &[(&str, Option<u64>)] = &[
+    ("2023-01-31-120000_create_users", Some(20230131120000)),
+    ("42_answer", Some(42)),
+    ("42_the_answer", Some(42)),
+    ("42", None),
+    ("42_", None),
+    ("42-x_answer", None),
+];
+
+/// Sets of migrations, as the `.sql` files in the root and the names of
+/// migration directories (with whether they have a `down.sql`), along with
+/// the version that is duplicated if they aren't valid.
+#[allow(clippy::type_complexity)]
+pub(crate) const LAYOUTS: &[(&[&str], &[(&str, bool)], Option<u64>)] = &[
+    (&["V1__a.sql", "U1__a.sql"], &[], None),
+    (
+        &["V1__a.sql", "V2__b.sql", "U2__b.sql"],
+        &[("3_c", true)],
+        None,
+    ),
+    (&["V1__a.sql", "U1__a.sql", "U1__b.sql"], &[], Some(1)),
+    (&["U1__a.sql"], &[("1_a", true)], Some(1)),
+    (&["U1__a.sql"], &[("1_a", false)], None),
+    (&["V1__a.sql"], &[("01_b", false)], Some(1)),
+];
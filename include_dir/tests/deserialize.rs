@@ -0,0 +1,71 @@
+#![cfg(all(feature = "json", feature = "toml", feature = "yaml"))]
+
+use include_dir::{include_dir, Dir, ParseErrorKind};
+use serde::Deserialize;
+use std::path::Path;
+
+static DATA: Dir<'_> = include_dir!(
+    "$CARGO_MANIFEST_DIR/tests/fixtures/data",
+    validate_syntax = true
+);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Endpoint<'a> {
+    host: &'a str,
+    port: u16,
+}
+
+#[test]
+fn parse_each_format() {
+    let server: Endpoint<'_> = DATA.get_file("server.json").unwrap().parse_json().unwrap();
+    assert_eq!(
+        server,
+        Endpoint {
+            host: "localhost",
+            port: 8080
+        }
+    );
+
+    let proxy: Endpoint<'_> = DATA.get_file("proxy.toml").unwrap().parse_toml().unwrap();
+    assert_eq!(proxy.port, 3128);
+
+    let client: Endpoint<'_> = DATA.get_file("client.yaml").unwrap().parse_yaml().unwrap();
+    assert_eq!(client.host, "example.com");
+}
+
+#[test]
+fn errors_mention_the_path() {
+    let err = DATA
+        .get_file("server.json")
+        .unwrap()
+        .parse_toml::<Endpoint<'_>>()
+        .unwrap_err();
+
+    assert_eq!(err.path(), Path::new("server.json"));
+    assert!(matches!(err.kind(), ParseErrorKind::Toml(_)));
+    let message = err.to_string();
+    assert!(message.starts_with("Unable to parse \"server.json\": "));
+    assert!(message.ends_with(&std::error::Error::source(&err).unwrap().to_string()));
+}
+
+#[test]
+#[cfg(feature = "glob")]
+fn load_everything() {
+    let endpoints = DATA.load_all::<Endpoint<'_>>("*").unwrap();
+
+    let ports: Vec<_> = endpoints
+        .iter()
+        .map(|(path, endpoint)| (path.to_str().unwrap(), endpoint.port))
+        .collect();
+    assert_eq!(
+        ports,
+        [
+            ("client.yaml", 443),
+            ("proxy.toml", 3128),
+            ("server.json", 8080)
+        ]
+    );
+
+    let err = DATA.load_all::<Endpoint<'_>>("[").unwrap_err();
+    assert!(matches!(err.kind(), ParseErrorKind::Pattern(_)));
+}
@@ -0,0 +1,140 @@
+use include_dir::{include_dir, ArchiveDir, Dir, DirBuf, DirEntry, DirEntryBuf, File};
+use std::path::Path;
+use tempfile::TempDir;
+
+static PARENT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR");
+
+#[test]
+fn insert_files_and_look_them_up() {
+    let mut dir = DirBuf::new();
+
+    assert!(dir.insert_file("b.txt", "b").is_none());
+    assert!(dir.insert_file("a/nested/c.txt", "c").is_none());
+    assert!(dir.insert_file("./a\\d.txt", "d").is_none());
+
+    let paths: Vec<_> = dir.walk().map(|e| e.path().to_path_buf()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            Path::new("a"),
+            Path::new("a/d.txt"),
+            Path::new("a/nested"),
+            Path::new("a/nested/c.txt"),
+            Path::new("b.txt"),
+        ]
+    );
+
+    assert_eq!(
+        dir.get_file("a/nested/c.txt").unwrap().contents_utf8(),
+        Some("c")
+    );
+    assert_eq!(dir.get_file("a/../b.txt").unwrap().contents(), b"b");
+    assert!(dir.get_file("a").is_none());
+    assert!(dir.get_dir("a/nested").is_some());
+    assert!(!dir.contains("missing.txt"));
+
+    // lookups on sub-directories use paths relative to the root, like Dir
+    let a = dir.get_dir("a").unwrap();
+    assert!(a.get_file("a/d.txt").is_some());
+    assert!(a.get_file("b.txt").is_none());
+}
+
+#[test]
+fn inserting_replaces_existing_entries() {
+    let mut dir = DirBuf::new();
+    dir.insert_file("config.toml", "old");
+
+    let previous = dir.insert_file("config.toml", "new").unwrap();
+    assert_eq!(previous.as_file().unwrap().contents(), b"old");
+    assert_eq!(dir.get_file("config.toml").unwrap().contents(), b"new");
+
+    // a file that needs to become a directory gets replaced
+    dir.insert_file("config.toml/nested.txt", "nested");
+    assert!(dir.get_dir("config.toml").is_some());
+    assert_eq!(dir.entries().len(), 1);
+}
+
+#[test]
+fn remove_entries() {
+    let mut dir = DirBuf::new();
+    dir.insert_file("a/b.txt", "b");
+    dir.insert_file("a/c.txt", "c");
+
+    let removed = dir.remove("a/b.txt").unwrap();
+    assert_eq!(removed.path(), Path::new("a/b.txt"));
+    assert!(dir.remove("a/b.txt").is_none());
+
+    assert!(matches!(dir.remove("a"), Some(DirEntryBuf::Dir(_))));
+    assert!(dir.entries().is_empty());
+}
+
+#[test]
+fn convert_from_an_embedded_dir() {
+    let dir = DirBuf::from(&PARENT_DIR);
+
+    assert_eq!(
+        dir.get_file("src/lib.rs").unwrap().contents(),
+        include_bytes!("../src/lib.rs")
+    );
+    assert_eq!(
+        dir.walk().count(),
+        PARENT_DIR.walk().count(),
+        "Every entry should be copied"
+    );
+}
+
+#[test]
+fn convert_a_hand_written_dir_with_unprefixed_paths() {
+    // the children's paths should start with "données/", but nothing
+    // enforces that for a hand-written Dir
+    static ENTRIES: &[DirEntry<'_>] = &[DirEntry::File(File::new("é.txt", b"accent"))];
+    static ROOT: &[DirEntry<'_>] = &[DirEntry::Dir(Dir::new("données", ENTRIES))];
+    let dir = DirBuf::from(&Dir::new("", ROOT));
+
+    assert!(dir.get_dir("données").is_some());
+    assert_eq!(dir.get_file("données/é.txt").unwrap().contents(), b"accent");
+    assert!(dir.get_file("données/éé").is_none());
+}
+
+#[test]
+fn unprefixed_children_are_sorted_by_name() {
+    // sorting by full path would put "a/b.txt" first, but lookups go by name
+    static ENTRIES: &[DirEntry<'_>] = &[
+        DirEntry::File(File::new("a/b.txt", b"b")),
+        DirEntry::File(File::new("b/a.txt", b"a")),
+    ];
+    static ROOT: &[DirEntry<'_>] = &[DirEntry::Dir(Dir::new("docs", ENTRIES))];
+    let dir = DirBuf::from(&Dir::new("", ROOT));
+
+    assert_eq!(dir.get_file("docs/a.txt").unwrap().contents(), b"a");
+    assert_eq!(dir.get_file("docs/b.txt").unwrap().contents(), b"b");
+    assert!(dir.get_file("docs/b/a.txt").is_none());
+}
+
+#[test]
+fn convert_from_an_archive() {
+    let bytes = PARENT_DIR.to_archive_bytes();
+    let archive = ArchiveDir::from_bytes(&bytes).unwrap();
+
+    assert_eq!(DirBuf::from(archive), DirBuf::from(&PARENT_DIR));
+}
+
+#[test]
+fn read_from_disk_and_extract_again() {
+    let mut original = DirBuf::new();
+    original.insert_file("index.html", "<h1>Hello</h1>");
+    original.insert_file("css/site.css", "h1 {}");
+    original.insert_file("css/fonts/font.woff2", vec![0, 159, 146, 150]);
+    let tmpdir = TempDir::new().unwrap();
+    original.extract(tmpdir.path()).unwrap();
+
+    let from_disk = DirBuf::from_fs(tmpdir.path()).unwrap();
+
+    assert_eq!(from_disk, original);
+}
+
+#[test]
+#[should_panic]
+fn inserting_the_root_panics() {
+    DirBuf::new().insert_file("./", "");
+}
@@ -0,0 +1,65 @@
+use include_dir::{include_dir, include_file, Dir, File};
+
+static ASSETS: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures", fingerprint = true);
+static PLAIN: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures");
+static CARGO_TOML: File<'_> = include_file!("$CARGO_MANIFEST_DIR/Cargo.toml", fingerprint = true);
+
+#[test]
+fn every_file_gets_a_fingerprinted_path() {
+    for file in ASSETS.walk().filter_map(|e| e.as_file()) {
+        let fingerprinted = file.fingerprinted_path().unwrap();
+
+        assert_ne!(fingerprinted, file.path());
+        assert_eq!(fingerprinted.parent(), file.path().parent());
+        assert_eq!(fingerprinted.extension(), file.path().extension());
+    }
+
+    let server = ASSETS.get_file("data/server.json").unwrap();
+    let name = server.fingerprinted_path().unwrap().to_str().unwrap();
+    let hash = name
+        .strip_prefix("data/server.")
+        .and_then(|rest| rest.strip_suffix(".json"))
+        .unwrap();
+    assert_eq!(hash.len(), 8);
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+    assert!(CARGO_TOML.fingerprinted_path().is_some());
+    assert!(PLAIN
+        .walk()
+        .filter_map(|e| e.as_file())
+        .all(|f| f.fingerprinted_path().is_none()));
+}
+
+#[test]
+fn look_up_fingerprinted_paths() {
+    let server = ASSETS.get_file("data/server.json").unwrap();
+    let fingerprinted = server.fingerprinted_path().unwrap();
+
+    assert_eq!(
+        ASSETS.get_by_fingerprinted_path(fingerprinted),
+        Some(server)
+    );
+    assert_eq!(
+        ASSETS.get_by_fingerprinted_path(format!("/{}", fingerprinted.display())),
+        Some(server)
+    );
+    assert!(ASSETS
+        .get_by_fingerprinted_path("data/server.json")
+        .is_none());
+    assert!(ASSETS
+        .get_by_fingerprinted_path("missing/server.0123abcd.json")
+        .is_none());
+}
+
+#[test]
+fn asset_urls() {
+    let server = ASSETS.get_file("data/server.json").unwrap();
+    let expected = format!("/{}", server.fingerprinted_path().unwrap().display());
+
+    assert_eq!(ASSETS.asset_url("data/server.json"), Some(expected));
+    assert_eq!(
+        PLAIN.asset_url("data/server.json").as_deref(),
+        Some("/data/server.json")
+    );
+    assert_eq!(ASSETS.asset_url("data/missing.json"), None);
+}
@@ -0,0 +1 @@
+not-found = Page not found
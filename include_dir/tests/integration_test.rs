@@ -1,8 +1,9 @@
-use include_dir::{include_dir, Dir};
+use include_dir::{include_dir, include_file, Dir, File};
 use std::path::Path;
 use tempfile::TempDir;
 
 static PARENT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR");
+static CARGO_TOML: File<'_> = include_file!("$CARGO_MANIFEST_DIR/Cargo.toml");
 
 #[test]
 fn included_all_files_in_the_include_dir_crate() {
@@ -12,6 +13,194 @@ fn included_all_files_in_the_include_dir_crate() {
     assert!(PARENT_DIR.contains("src/lib.rs"));
 }
 
+#[test]
+fn walk_visits_every_entry_in_order() {
+    let walked: Vec<_> = PARENT_DIR.walk().map(|e| e.path()).collect();
+
+    let src = walked.iter().position(|p| *p == Path::new("src")).unwrap();
+    assert_eq!(walked[src + 1], Path::new("src/archive.rs"));
+    assert!(walked.contains(&Path::new("tests/integration_test.rs")));
+}
+
+#[test]
+fn include_a_single_file() {
+    assert_eq!(CARGO_TOML.path(), Path::new("Cargo.toml"));
+    assert_eq!(CARGO_TOML.contents(), include_bytes!("../Cargo.toml"));
+    assert_eq!(
+        PARENT_DIR.get_file("Cargo.toml").unwrap().contents(),
+        CARGO_TOML.contents()
+    );
+}
+
+#[test]
+fn mount_several_directories() {
+    static MOUNTED: Dir<'_> = include_dir!({
+        "" => "$CARGO_MANIFEST_DIR/src",
+        "tests" => "$CARGO_MANIFEST_DIR/tests",
+        "macros/src" => "$CARGO_MANIFEST_DIR/../macros/src",
+    });
+
+    assert_eq!(
+        MOUNTED.get_file("lib.rs").unwrap().contents(),
+        include_bytes!("../src/lib.rs")
+    );
+    assert!(MOUNTED.contains("tests/integration_test.rs"));
+    assert_eq!(
+        MOUNTED.get_dir("macros").unwrap().path(),
+        Path::new("macros")
+    );
+    assert_eq!(
+        MOUNTED.get_file("macros/src/lib.rs").unwrap().contents(),
+        include_bytes!("../../macros/src/lib.rs")
+    );
+}
+
+#[test]
+fn overlapping_mounts() {
+    static FIRST: Dir<'_> = include_dir!(
+        {
+            "" => "$CARGO_MANIFEST_DIR/src",
+            "" => "$CARGO_MANIFEST_DIR/../macros/src",
+        },
+        on_conflict = "first",
+    );
+    static LAST: Dir<'_> = include_dir!(
+        {
+            "" => "$CARGO_MANIFEST_DIR/src",
+            "" => "$CARGO_MANIFEST_DIR/../macros/src",
+        },
+        on_conflict = "last",
+    );
+
+    assert_eq!(
+        FIRST.get_file("lib.rs").unwrap().contents(),
+        include_bytes!("../src/lib.rs")
+    );
+    assert_eq!(
+        LAST.get_file("lib.rs").unwrap().contents(),
+        include_bytes!("../../macros/src/lib.rs")
+    );
+    assert!(FIRST.contains("dir.rs") && FIRST.contains("options.rs"));
+}
+
+#[test]
+fn size_limits_and_reports() {
+    #[allow(deprecated)]
+    static LIMITED: Dir<'_> = include_dir!(
+        "$CARGO_MANIFEST_DIR/tests/fixtures",
+        max_file_size = "64 KiB",
+        max_total_size = 1_000_000,
+        max_files = 1000,
+        size_report = true,
+    );
+    static FIXTURES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures");
+
+    assert_eq!(LIMITED, FIXTURES);
+}
+
+#[cfg(feature = "metadata")]
+static WITH_METADATA: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR", metadata = true);
+
+#[test]
+#[cfg(feature = "metadata")]
+fn metadata_is_only_embedded_when_requested() {
+    assert!(PARENT_DIR.walk().all(|e| e.metadata().is_none()));
+    assert!(CARGO_TOML.metadata().is_none());
+    assert!(WITH_METADATA.walk().all(|e| e.metadata().is_some()));
+}
+
+#[test]
+#[cfg(feature = "metadata")]
+fn metadata_is_embedded() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let on_disk = std::fs::metadata(root.join("Cargo.toml")).unwrap();
+
+    let metadata = WITH_METADATA
+        .get_file("Cargo.toml")
+        .unwrap()
+        .metadata()
+        .unwrap();
+    if option_env!("SOURCE_DATE_EPOCH").is_none() {
+        assert_eq!(metadata.modified(), on_disk.modified().ok());
+        assert_eq!(metadata.created(), on_disk.created().ok());
+    }
+    assert_eq!(metadata.len(), on_disk.len());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(metadata.mode(), Some(on_disk.mode()));
+
+        if option_env!("SOURCE_DATE_EPOCH").is_none() {
+            assert_eq!(metadata.uid(), Some(on_disk.uid()));
+            assert_eq!(metadata.gid(), Some(on_disk.gid()));
+        } else {
+            assert_eq!(metadata.uid(), None);
+        }
+    }
+
+    let src = WITH_METADATA.get_dir("src").unwrap().metadata().unwrap();
+    assert_eq!(src.len(), 0);
+    assert!(src.modified().is_some());
+    assert!(WITH_METADATA.metadata().is_some());
+}
+
+#[test]
+#[cfg(feature = "metadata")]
+fn timestamps_are_clamped_to_source_date_epoch() {
+    let epoch = match option_env!("SOURCE_DATE_EPOCH") {
+        Some(secs) if !secs.is_empty() => secs.parse().unwrap(),
+        _ => return,
+    };
+    let epoch = std::time::UNIX_EPOCH + std::time::Duration::from_secs(epoch);
+
+    for entry in WITH_METADATA.walk() {
+        let metadata = entry.metadata().unwrap();
+        let times = [metadata.accessed(), metadata.created(), metadata.modified()];
+
+        for time in times.iter().flatten() {
+            assert!(*time <= epoch, "{}", entry.path().display());
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "metadata")]
+fn modified_times_can_come_from_git() {
+    static SRC: Dir<'_> = include_dir!(
+        "$CARGO_MANIFEST_DIR/src",
+        metadata = true,
+        metadata_modified = "git",
+        metadata_accessed = false,
+    );
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "-1", "--format=%ct", "--", "src/lib.rs"])
+        .output();
+    let committed: u64 = match output {
+        Ok(output) if output.status.success() && !output.stdout.is_empty() => {
+            String::from_utf8(output.stdout)
+                .unwrap()
+                .trim()
+                .parse()
+                .unwrap()
+        }
+        // not inside a git checkout, or lib.rs was never committed
+        _ => return,
+    };
+    let committed = std::time::UNIX_EPOCH + std::time::Duration::from_secs(committed);
+
+    let lib_rs = SRC.get_file("lib.rs").unwrap().metadata().unwrap();
+    assert!(lib_rs.accessed().is_none());
+
+    if option_env!("SOURCE_DATE_EPOCH").is_none() {
+        assert_eq!(lib_rs.modified(), Some(committed));
+    }
+}
+
 #[test]
 fn extract_all_files() {
     let tmpdir = TempDir::new().unwrap();
@@ -21,6 +210,64 @@ fn extract_all_files() {
     validate_extracted(&PARENT_DIR, root);
 }
 
+#[test]
+fn lookup_paths_are_normalized() {
+    let lib_rs = PARENT_DIR.get_file("src/lib.rs").unwrap();
+
+    for path in [
+        "./src/lib.rs",
+        "/src/lib.rs",
+        "src\\lib.rs",
+        "src/./lib.rs",
+        "tests/../src/lib.rs",
+        "../../src/lib.rs",
+    ] {
+        assert_eq!(PARENT_DIR.get_file(path), Some(lib_rs), "{}", path);
+    }
+
+    assert!(PARENT_DIR.get_dir("/src/").is_some());
+    assert!(PARENT_DIR.get_entry("/").is_none());
+    assert!(PARENT_DIR.get_entry("..").is_none());
+}
+
+#[test]
+fn strict_lookups_require_an_exact_match() {
+    assert!(PARENT_DIR.get_file_strict("src/lib.rs").is_some());
+    assert!(PARENT_DIR.get_file_strict("./src/lib.rs").is_none());
+    assert!(PARENT_DIR.get_dir_strict("/src").is_none());
+}
+
+#[test]
+fn case_insensitive_lookups() {
+    let lib_rs = PARENT_DIR.get_file("src/lib.rs").unwrap();
+
+    assert_eq!(PARENT_DIR.get_file_ignore_case("SRC/Lib.RS"), Some(lib_rs));
+    assert_eq!(
+        PARENT_DIR.get_file_ignore_case("./Src\\LIB.rs"),
+        Some(lib_rs)
+    );
+    assert!(PARENT_DIR.get_dir_ignore_case("TESTS").is_some());
+    assert!(PARENT_DIR.get_file("SRC/Lib.RS").is_none());
+}
+
+#[test]
+fn lookups_fall_back_to_the_composed_form() {
+    const ENTRIES: &[include_dir::DirEntry<'_>] = &[
+        include_dir::DirEntry::File(File::new("caf\u{e9}.txt", b"composed")),
+        include_dir::DirEntry::File(File::new("nai\u{308}ve.txt", b"decomposed")),
+    ];
+    const DIR: Dir<'_> = Dir::new("", ENTRIES);
+
+    assert!(DIR.get_file("caf\u{e9}.txt").is_some());
+    assert!(DIR.get_file("nai\u{308}ve.txt").is_some());
+    assert_eq!(
+        DIR.get_file("cafe\u{301}.txt").is_some(),
+        cfg!(feature = "nfc")
+    );
+    // paths embedded without `normalize_unicode = true` are left alone
+    assert!(DIR.get_file("na\u{ef}ve.txt").is_none());
+}
+
 // Validates that all files on the filesystem exist in the inclusion
 fn validate_included(dir: &Dir<'_>, path: &Path, root: &Path) {
     for entry in path.read_dir().unwrap() {
@@ -44,17 +291,12 @@ fn validate_included(dir: &Dir<'_>, path: &Path, root: &Path) {
 
 // Validates that all files in the inclusion were extracted to the filesystem
 fn validate_extracted(dir: &Dir, path: &Path) {
-    // Check if all the subdirectories exist, recursing on each
-    for subdir in dir.dirs() {
-        let subdir_path = path.join(dir.path());
-        assert!(subdir_path.exists());
-        validate_extracted(subdir, &subdir_path);
-    }
-
-    // Check if the files at the root of this directory exist
-    for file in dir.files() {
-        let file_path = path.join(file.path());
-        assert!(file_path.exists());
+    // Every entry's path is relative to the root of the embedded directory,
+    // so it is always joined onto the directory we extracted to, however
+    // deeply it is nested
+    for entry in dir.walk() {
+        let entry_path = path.join(entry.path());
+        assert!(entry_path.exists(), "{} is missing", entry_path.display());
     }
 }
 
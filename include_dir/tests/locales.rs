@@ -0,0 +1,94 @@
+#![cfg(feature = "fluent")]
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use include_dir::{include_dir, Dir, DirEntry, File, LocaleDir, LocaleError};
+
+static LOCALES: Dir<'_> = include_dir!(
+    "$CARGO_MANIFEST_DIR/tests/fixtures/locales",
+    validate_locales = true,
+);
+
+fn format(
+    bundle: &FluentBundle<FluentResource>,
+    id: &str,
+    args: Option<&FluentArgs<'_>>,
+) -> String {
+    let message = bundle.get_message(id).unwrap();
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(message.value().unwrap(), args, &mut errors);
+    assert!(errors.is_empty(), "{:?}", errors);
+    // strip the Unicode isolation marks around placeables
+    text.replace(['\u{2068}', '\u{2069}'], "")
+}
+
+#[test]
+fn list_locales() {
+    let locales = LocaleDir::new(&LOCALES).locales();
+
+    let names: Vec<_> = locales.iter().map(|l| l.to_string()).collect();
+    assert_eq!(names, ["en", "fr"]);
+}
+
+#[test]
+fn negotiate_a_regional_variant() {
+    let bundle = LocaleDir::new(&LOCALES)
+        .bundle_for(&["fr-CA", "fr", "en"])
+        .unwrap();
+
+    assert_eq!(bundle.locales[0].to_string(), "fr");
+    assert_eq!(format(&bundle, "hello", None), "Bonjour !");
+    assert_eq!(format(&bundle, "not-found", None), "Page introuvable");
+}
+
+#[test]
+fn fall_back_to_less_preferred_locales() {
+    let bundle = LocaleDir::new(&LOCALES).bundle_for(&["fr", "en"]).unwrap();
+
+    let mut args = FluentArgs::new();
+    args.set("name", "Michael");
+
+    assert_eq!(
+        format(&bundle, "farewell", Some(&args)),
+        "Goodbye, Michael!"
+    );
+}
+
+#[test]
+fn no_matching_locale() {
+    let result = LocaleDir::new(&LOCALES).bundle_for(&["de-DE"]);
+
+    assert!(matches!(result, Err(LocaleError::NoMatchingLocale)));
+}
+
+#[test]
+fn duplicate_messages_in_one_locale_are_errors() {
+    static EN: &[DirEntry<'_>] = &[
+        DirEntry::File(File::new("en/a.ftl", b"hello = Hello!")),
+        DirEntry::File(File::new("en/b.ftl", b"hello = Hi!")),
+    ];
+    static FR: &[DirEntry<'_>] = &[DirEntry::File(File::new(
+        "fr/main.ftl",
+        "hello = Bonjour !".as_bytes(),
+    ))];
+    static ROOT: &[DirEntry<'_>] = &[
+        DirEntry::Dir(Dir::new("en", EN)),
+        DirEntry::Dir(Dir::new("fr", FR)),
+    ];
+    static DUPLICATES: Dir<'_> = Dir::new("", ROOT);
+    let locales = LocaleDir::new(&DUPLICATES);
+
+    match locales.bundle_for(&["fr", "en"]) {
+        Err(LocaleError::Duplicate { path, errors }) => {
+            assert_eq!(path, std::path::Path::new("en/b.ftl"));
+            assert_eq!(errors.len(), 1);
+        }
+        other => panic!(
+            "Expected a duplicate message error, got {:?}",
+            other.map(|_| ())
+        ),
+    }
+
+    // the problem is only reported when "en" is used
+    let bundle = locales.bundle_for(&["fr"]).unwrap();
+    assert_eq!(format(&bundle, "hello", None), "Bonjour !");
+}
@@ -0,0 +1,138 @@
+use include_dir::{include_dir, Dir, DirEntry, File};
+use std::path::Path;
+
+static TREE: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests");
+
+fn count_by_walking(dir: &Dir<'_>) -> (usize, u64) {
+    dir.walk()
+        .filter_map(DirEntry::as_file)
+        .fold((0, 0), |(count, size), f| {
+            (count + 1, size + f.contents().len() as u64)
+        })
+}
+
+#[test]
+fn generated_stats_match_the_tree() {
+    assert_eq!(
+        (TREE.file_count(), TREE.total_size()),
+        count_by_walking(&TREE)
+    );
+
+    let fixtures = TREE.get_dir("fixtures").unwrap();
+    assert_eq!(
+        (fixtures.file_count(), fixtures.total_size()),
+        count_by_walking(fixtures)
+    );
+}
+
+#[test]
+fn stats_for_hand_written_dirs() {
+    const NESTED: &[DirEntry<'_>] = &[DirEntry::File(File::new("a/b.txt", b"hello"))];
+    const ENTRIES: &[DirEntry<'_>] = &[
+        DirEntry::Dir(Dir::new("a", NESTED)),
+        DirEntry::File(File::new("c.txt", b"world!")),
+    ];
+    const DIR: Dir<'_> = Dir::new("", ENTRIES);
+
+    // these are const fns, so they can be evaluated at compile time
+    const FILE_COUNT: usize = DIR.file_count();
+    const TOTAL_SIZE: u64 = DIR.total_size();
+
+    assert_eq!(FILE_COUNT, 2);
+    assert_eq!(TOTAL_SIZE, 11);
+}
+
+#[test]
+fn stats_dont_affect_equality() {
+    let hand_written = Dir::new(TREE.path().to_str().unwrap(), TREE.entries());
+
+    assert_eq!(hand_written, TREE);
+}
+
+#[test]
+fn list_every_file() {
+    let manifest = TREE.manifest();
+
+    assert_eq!(manifest.file_count(), TREE.file_count());
+    assert_eq!(manifest.total_size(), TREE.total_size());
+
+    let paths: Vec<_> = manifest.files().map(|f| f.path()).collect();
+    let expected: Vec<_> = TREE
+        .walk()
+        .filter_map(|e| e.as_file())
+        .map(|f| f.path())
+        .collect();
+    assert_eq!(paths, expected);
+
+    let json = manifest.get("fixtures/data/server.json").unwrap();
+    let contents = TREE
+        .get_file("fixtures/data/server.json")
+        .unwrap()
+        .contents();
+    assert_eq!(json.size(), contents.len() as u64);
+    assert_eq!(json.mime(), "application/json");
+    assert_ne!(json.hash(), 0);
+
+    let rust = manifest.get("manifest.rs").unwrap();
+    assert_eq!(rust.mime(), "text/plain");
+    assert!(manifest.get("missing.rs").is_none());
+}
+
+#[test]
+fn hashes_are_only_embedded_when_fingerprinting() {
+    static FINGERPRINTED: Dir<'_> =
+        include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures", fingerprint = true);
+
+    for (dir, embedded) in [(&TREE, false), (&FINGERPRINTED, true)] {
+        for file in dir.walk().filter_map(DirEntry::as_file) {
+            let computed = File::new("computed", file.contents()).hash();
+
+            assert_eq!(file.hash(), computed, "{}", file.path().display());
+            assert_eq!(format!("{:?}", file).contains("hash: Some("), embedded);
+            assert_eq!(dir.manifest().get(file.path()).unwrap().hash(), computed);
+        }
+    }
+}
+
+#[test]
+fn sub_directory_paths_are_from_the_root() {
+    let fixtures = TREE.get_dir("fixtures").unwrap();
+    let manifest = fixtures.manifest();
+
+    let json = manifest.get("fixtures/data/server.json").unwrap();
+    assert_eq!(json.path(), Path::new("fixtures/data/server.json"));
+    assert!(manifest.get("data/server.json").is_none());
+}
+
+#[test]
+fn look_up_files_in_unsorted_dirs() {
+    static ENTRIES: &[DirEntry<'_>] = &[
+        DirEntry::File(File::new("b.txt", b"b")),
+        DirEntry::File(File::new("a.txt", b"a")),
+    ];
+    let manifest = Dir::new("", ENTRIES).manifest();
+
+    assert_eq!(manifest.get("a.txt").unwrap().size(), 1);
+    assert_eq!(manifest.get("b.txt").unwrap().size(), 1);
+}
+
+#[test]
+#[cfg(all(feature = "serde", feature = "json"))]
+fn serialize_the_manifest() {
+    let manifest = TREE.manifest();
+    let json = serde_json::to_value(&manifest).unwrap();
+
+    assert_eq!(json["file_count"], TREE.file_count());
+    assert_eq!(json["total_size"], TREE.total_size());
+
+    let entry = manifest.get("fixtures/data/server.json").unwrap();
+    let serialized = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["path"] == "fixtures/data/server.json")
+        .unwrap();
+    assert_eq!(serialized["size"], entry.size());
+    assert_eq!(serialized["hash"], format!("{:016x}", entry.hash()));
+    assert_eq!(serialized["mime"], "application/json");
+}
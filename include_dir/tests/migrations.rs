@@ -0,0 +1,240 @@
+#![cfg(feature = "migrations")]
+
+use include_dir::{include_dir, Dir, DirEntry, File, MigrationErrorKind};
+
+static MIGRATIONS: Dir<'_> = include_dir!(
+    "$CARGO_MANIFEST_DIR/tests/fixtures/migrations",
+    validate_migrations = true,
+);
+
+#[test]
+fn discover_both_layouts() {
+    let migrations = MIGRATIONS.migrations().unwrap();
+
+    let versions: Vec<_> = migrations.iter().map(|m| m.version()).collect();
+    assert_eq!(versions, [1, 2, 20230131120000]);
+
+    let create_users = migrations.get(1).unwrap();
+    assert_eq!(create_users.name(), "create_users");
+    assert!(create_users.up().starts_with("CREATE TABLE users"));
+    assert_eq!(create_users.down(), Some("DROP TABLE users;\n"));
+    assert_ne!(
+        create_users.checksum(),
+        migrations.get(2).unwrap().checksum()
+    );
+
+    assert_eq!(migrations.get(2).unwrap().down(), None);
+
+    let create_posts = migrations.get(20230131120000).unwrap();
+    assert_eq!(create_posts.name(), "create_posts");
+    assert_eq!(create_posts.down(), Some("DROP TABLE posts;\n"));
+}
+
+#[test]
+fn duplicate_versions_are_rejected() {
+    static ENTRIES: &[DirEntry<'_>] = &[
+        DirEntry::File(File::new("V1__first.sql", b"SELECT 1;")),
+        DirEntry::File(File::new("V01__second.sql", b"SELECT 2;")),
+    ];
+    let dir = Dir::new("", ENTRIES);
+
+    let err = dir.migrations().unwrap_err();
+
+    assert_eq!(err.kind(), &MigrationErrorKind::DuplicateVersion(1));
+}
+
+#[test]
+fn malformed_migrations_are_rejected() {
+    static BAD_NAME: &[DirEntry<'_>] = &[DirEntry::File(File::new("V1_typo.sql", b""))];
+    let err = Dir::new("", BAD_NAME).migrations().unwrap_err();
+    assert_eq!(err.kind(), &MigrationErrorKind::InvalidName);
+    assert_eq!(err.path().to_str(), Some("V1_typo.sql"));
+
+    static ORPHANED_UNDO: &[DirEntry<'_>] = &[DirEntry::File(File::new("U3__oops.sql", b""))];
+    let err = Dir::new("", ORPHANED_UNDO).migrations().unwrap_err();
+    assert_eq!(err.kind(), &MigrationErrorKind::MissingUp);
+
+    static NO_UP: &[DirEntry<'_>] = &[DirEntry::Dir(Dir::new(
+        "20230101_init",
+        &[DirEntry::File(File::new("20230101_init/down.sql", b""))],
+    ))];
+    let err = Dir::new("", NO_UP).migrations().unwrap_err();
+    assert_eq!(err.kind(), &MigrationErrorKind::MissingUp);
+}
+
+#[path = "common/migration_names.rs"]
+mod migration_names;
+
+#[test]
+fn names_are_parsed_like_the_macro() {
+    for (name, expected) in migration_names::FILE_NAMES {
+        let entries = [DirEntry::File(File::new(name, b"SELECT 1;"))];
+        let result = Dir::new("", &entries).migrations();
+
+        match expected {
+            Some(('V', version)) => {
+                let versions: Vec<_> = result.unwrap().iter().map(|m| m.version()).collect();
+                assert_eq!(versions, [*version], "{}", name);
+            }
+            // an undo script on its own is only missing its migration
+            Some(_) => assert_eq!(
+                result.unwrap_err().kind(),
+                &MigrationErrorKind::MissingUp,
+                "{}",
+                name
+            ),
+            None => assert_eq!(
+                result.unwrap_err().kind(),
+                &MigrationErrorKind::InvalidName,
+                "{}",
+                name
+            ),
+        }
+    }
+
+    for (name, expected) in migration_names::DIR_NAMES {
+        let up = format!("{}/up.sql", name);
+        let files = [DirEntry::File(File::new(&up, b"SELECT 1;"))];
+        let entries = [DirEntry::Dir(Dir::new(name, &files))];
+        let result = Dir::new("", &entries).migrations();
+
+        match expected {
+            Some(version) => {
+                let versions: Vec<_> = result.unwrap().iter().map(|m| m.version()).collect();
+                assert_eq!(versions, [*version], "{}", name);
+            }
+            None => assert_eq!(
+                result.unwrap_err().kind(),
+                &MigrationErrorKind::InvalidName,
+                "{}",
+                name
+            ),
+        }
+    }
+}
+
+#[test]
+fn layouts_are_validated_like_the_macro() {
+    for (files, dirs, duplicate) in migration_names::LAYOUTS {
+        let scripts: Vec<Vec<String>> = dirs
+            .iter()
+            .map(|(name, has_down)| {
+                let mut scripts = vec![format!("{}/up.sql", name)];
+                if *has_down {
+                    scripts.push(format!("{}/down.sql", name));
+                }
+                scripts
+            })
+            .collect();
+        let dir_entries: Vec<Vec<DirEntry<'_>>> = scripts
+            .iter()
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|p| DirEntry::File(File::new(p, b"SELECT 1;")))
+                    .collect()
+            })
+            .collect();
+
+        let mut entries: Vec<DirEntry<'_>> = files
+            .iter()
+            .map(|name| DirEntry::File(File::new(name, b"SELECT 1;")))
+            .collect();
+        for ((name, _), children) in dirs.iter().zip(&dir_entries) {
+            entries.push(DirEntry::Dir(Dir::new(name, children)));
+        }
+
+        let result = Dir::new("", &entries).migrations();
+
+        match duplicate {
+            Some(version) => assert_eq!(
+                result.unwrap_err().kind(),
+                &MigrationErrorKind::DuplicateVersion(*version),
+                "{:?}",
+                files
+            ),
+            None => assert!(result.is_ok(), "{:?}", files),
+        }
+    }
+}
+
+#[test]
+fn unrelated_files_and_dirs_are_ignored() {
+    static ENTRIES: &[DirEntry<'_>] = &[
+        DirEntry::Dir(Dir::new("seeds", &[])),
+        DirEntry::File(File::new("README.md", b"# Migrations")),
+        DirEntry::File(File::new("V1__init.sql", b"SELECT 1;")),
+    ];
+
+    assert_eq!(Dir::new("", ENTRIES).migrations().unwrap().len(), 1);
+}
+
+#[cfg(feature = "rusqlite")]
+mod sqlite {
+    use super::*;
+    use include_dir::ApplyError;
+    use rusqlite::Connection;
+
+    fn tables(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'include_dir%' ORDER BY name")
+            .unwrap();
+        let names = stmt.query_map([], |row| row.get(0)).unwrap();
+        names.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn apply_to_an_in_memory_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrations = MIGRATIONS.migrations().unwrap();
+
+        let applied = migrations.apply(&mut conn).unwrap();
+
+        assert_eq!(applied, [1, 2, 20230131120000]);
+        assert_eq!(tables(&conn), ["posts", "users"]);
+        conn.execute(
+            "INSERT INTO users (name, email) VALUES ('Michael', 'michael@example.com')",
+            [],
+        )
+        .unwrap();
+
+        // applying again is a no-op
+        assert!(migrations.apply(&mut conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn revert_migrations() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrations = MIGRATIONS.migrations().unwrap();
+        migrations.apply(&mut conn).unwrap();
+
+        let reverted = migrations.revert_to(&mut conn, 2).unwrap();
+        assert_eq!(reverted, [20230131120000]);
+        assert_eq!(tables(&conn), ["users"]);
+
+        // "add_email" has no down script
+        let err = migrations.revert_to(&mut conn, 0).unwrap_err();
+        assert!(matches!(err, ApplyError::Irreversible { version: 2 }));
+        assert_eq!(tables(&conn), ["users"]);
+    }
+
+    #[test]
+    fn edited_migrations_are_detected() {
+        static ORIGINAL: &[DirEntry<'_>] = &[DirEntry::File(File::new(
+            "V1__init.sql",
+            b"CREATE TABLE a (id INTEGER);",
+        ))];
+        static EDITED: &[DirEntry<'_>] = &[DirEntry::File(File::new(
+            "V1__init.sql",
+            b"CREATE TABLE b (id INTEGER);",
+        ))];
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        let original = Dir::new("", ORIGINAL).migrations().unwrap();
+        original.apply(&mut conn).unwrap();
+        let edited = Dir::new("", EDITED).migrations().unwrap();
+        let err = edited.apply(&mut conn).unwrap_err();
+
+        assert!(matches!(err, ApplyError::ChecksumMismatch { version: 1 }));
+    }
+}
@@ -0,0 +1,141 @@
+use include_dir::{include_dir, Dir, Overlay, OverlayEntry};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+static PARENT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR");
+
+fn overrides() -> TempDir {
+    let tmpdir = TempDir::new().unwrap();
+    std::fs::create_dir(tmpdir.path().join("src")).unwrap();
+    std::fs::write(tmpdir.path().join("src").join("lib.rs"), "// overridden").unwrap();
+    std::fs::write(tmpdir.path().join("extra.txt"), "extra").unwrap();
+    tmpdir
+}
+
+#[test]
+fn earlier_layers_take_priority() {
+    let tmpdir = overrides();
+    let overlay = Overlay::new()
+        .with_path(tmpdir.path())
+        .with_dir(&PARENT_DIR);
+
+    let lib_rs = overlay.get_file("./src/lib.rs").unwrap().unwrap();
+    assert_eq!(lib_rs.contents_utf8(), Some("// overridden"));
+    assert!(!lib_rs.is_borrowed());
+
+    let cargo_toml = overlay.get_file("Cargo.toml").unwrap().unwrap();
+    assert_eq!(cargo_toml.contents(), include_bytes!("../Cargo.toml"));
+    assert!(cargo_toml.is_borrowed());
+
+    let reversed = Overlay::new()
+        .with_dir(&PARENT_DIR)
+        .with_path(tmpdir.path());
+    assert!(reversed
+        .get_file("src/lib.rs")
+        .unwrap()
+        .unwrap()
+        .is_borrowed());
+    assert_eq!(
+        reversed
+            .get_file("extra.txt")
+            .unwrap()
+            .unwrap()
+            .contents_utf8(),
+        Some("extra")
+    );
+}
+
+#[test]
+fn files_outside_a_layer_are_never_read() {
+    let tmpdir = overrides();
+    let overlay = Overlay::new().with_path(tmpdir.path().join("src"));
+
+    assert!(overlay.get_file("../extra.txt").unwrap().is_none());
+    assert!(overlay.get_file("lib.rs").unwrap().is_some());
+}
+
+#[test]
+fn entries_are_merged() {
+    let tmpdir = overrides();
+    let overlay = Overlay::new()
+        .with_path(tmpdir.path())
+        .with_dir(&PARENT_DIR);
+
+    let entries = overlay.entries();
+    assert!(entries.contains(&OverlayEntry::File(PathBuf::from("extra.txt"))));
+    assert!(entries.contains(&OverlayEntry::File(PathBuf::from("Cargo.toml"))));
+    assert_eq!(
+        entries
+            .iter()
+            .filter(|e| e.path() == Path::new("src"))
+            .count(),
+        1
+    );
+
+    let walked: Vec<_> = overlay.walk().collect();
+    assert!(walked.contains(&OverlayEntry::File(PathBuf::from("src/lib.rs"))));
+    assert!(walked.contains(&OverlayEntry::File(PathBuf::from("src/dir.rs"))));
+    assert!(walked
+        .windows(2)
+        .all(|pair| pair[0].path() < pair[1].path()));
+}
+
+#[test]
+#[cfg(unix)]
+fn unreadable_overrides_are_errors() {
+    let tmpdir = overrides();
+    std::os::unix::fs::symlink("loop", tmpdir.path().join("loop")).unwrap();
+    let overlay = Overlay::new()
+        .with_path(tmpdir.path())
+        .with_dir(&PARENT_DIR);
+
+    // a symlink loop can't be read, and that shouldn't look like a missing
+    // file
+    assert!(overlay.get_file("loop").is_err());
+    assert!(overlay.get_file("missing.txt").unwrap().is_none());
+    assert!(!overlay.contains("missing.txt").unwrap());
+}
+
+#[test]
+fn directories_fall_through_to_the_next_layer() {
+    let tmpdir = overrides();
+    std::fs::create_dir(tmpdir.path().join("Cargo.toml")).unwrap();
+    let overlay = Overlay::new()
+        .with_path(tmpdir.path())
+        .with_dir(&PARENT_DIR);
+
+    let cargo_toml = overlay.get_file("Cargo.toml").unwrap().unwrap();
+    assert!(cargo_toml.is_borrowed());
+    assert!(overlay.get_file("src").unwrap().is_none());
+    assert!(overlay.contains("src").unwrap());
+}
+
+#[test]
+fn embedded_layers_are_relative_to_their_dir() {
+    let src = PARENT_DIR.get_dir("src").unwrap();
+    let overlay = Overlay::new().with_dir(src);
+
+    let lib_rs = overlay.get_file("lib.rs").unwrap().unwrap();
+    assert_eq!(lib_rs.path(), Path::new("lib.rs"));
+    assert_eq!(lib_rs.contents(), include_bytes!("../src/lib.rs"));
+    assert!(overlay.contains("lib.rs").unwrap());
+    assert!(overlay.get_file("src/lib.rs").unwrap().is_none());
+
+    assert!(overlay
+        .entries()
+        .contains(&OverlayEntry::File(PathBuf::from("lib.rs"))));
+    assert!(overlay.walk().all(|e| !e.path().starts_with("src")));
+}
+
+#[test]
+#[cfg(feature = "glob")]
+fn find_in_every_layer() {
+    let tmpdir = overrides();
+    let overlay = Overlay::new()
+        .with_path(tmpdir.path())
+        .with_dir(&PARENT_DIR);
+
+    let found: Vec<_> = overlay.find("*.txt").unwrap().collect();
+
+    assert_eq!(found, vec![OverlayEntry::File(PathBuf::from("extra.txt"))]);
+}
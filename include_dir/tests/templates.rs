@@ -0,0 +1,99 @@
+#![cfg(any(feature = "tera", feature = "handlebars", feature = "minijinja"))]
+
+use include_dir::{include_dir, Dir, Templates};
+
+static TEMPLATES: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/tests/fixtures/templates");
+
+#[test]
+fn names_are_relative_to_the_dir() {
+    let partials = TEMPLATES.get_dir("partials").unwrap();
+    let templates = Templates::new(partials).with_extension("hbs");
+
+    let names: Vec<_> = templates.iter().map(|(name, _)| name).collect();
+
+    assert_eq!(names, ["header.hbs"]);
+    assert!(templates.get("header.hbs").is_some());
+    assert!(templates.get("welcome.jinja").is_none());
+    assert!(templates.get("../page.hbs").is_none());
+}
+
+#[test]
+fn filter_by_extension() {
+    let templates = Templates::new(&TEMPLATES)
+        .with_extension("tera")
+        .with_extension(".jinja");
+
+    let names: Vec<_> = templates.iter().map(|(name, _)| name).collect();
+
+    assert_eq!(
+        names,
+        [
+            "base.tera",
+            "greeting.jinja",
+            "pages/index.tera",
+            "partials/welcome.jinja"
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "tera")]
+fn render_with_tera() {
+    let mut tera = tera::Tera::default();
+    Templates::new(&TEMPLATES)
+        .with_extension("tera")
+        .register_tera(&mut tera)
+        .unwrap();
+
+    let mut context = tera::Context::new();
+    context.insert("title", "Home");
+    let rendered = tera.render("pages/index.tera", &context).unwrap();
+
+    assert_eq!(rendered, "<title>Home</title>\n");
+}
+
+#[test]
+#[cfg(feature = "handlebars")]
+fn render_with_handlebars() {
+    let mut registry = handlebars::Handlebars::new();
+    Templates::new(&TEMPLATES)
+        .with_extension("hbs")
+        .register_handlebars(&mut registry)
+        .unwrap();
+
+    let data = std::collections::BTreeMap::from([("title", "Home"), ("body", "Hi")]);
+    let rendered = registry.render("page", &data).unwrap();
+
+    assert_eq!(rendered, "<h1>Home</h1><p>Hi</p>");
+}
+
+#[test]
+#[cfg(feature = "minijinja")]
+fn render_with_minijinja() {
+    let mut env = minijinja::Environment::new();
+    env.set_loader(Templates::new(&TEMPLATES).minijinja_loader());
+
+    let welcome = env.get_template("partials/welcome.jinja").unwrap();
+    let rendered = welcome
+        .render(minijinja::context! { name => "World", place => "Rust" })
+        .unwrap();
+
+    assert_eq!(rendered, "Hello, World! Welcome to Rust.");
+    assert!(env.get_template("missing.jinja").is_err());
+}
+
+#[test]
+#[cfg(feature = "minijinja")]
+fn minijinja_rejects_binary_templates() {
+    static ENTRIES: &[include_dir::DirEntry<'_>] = &[include_dir::DirEntry::File(
+        include_dir::File::new("broken.jinja", b"\xff\xfe"),
+    )];
+    static BROKEN: Dir<'_> = Dir::new("", ENTRIES);
+
+    let mut env = minijinja::Environment::new();
+    env.set_loader(Templates::new(&BROKEN).minijinja_loader());
+
+    let err = env.get_template("broken.jinja").unwrap_err();
+    assert_eq!(err.kind(), minijinja::ErrorKind::InvalidOperation);
+    assert!(err.to_string().contains("broken.jinja"));
+}
@@ -0,0 +1,68 @@
+use include_dir::{include_dir, include_file, Dir, File};
+
+static RAW: File<'_> = include_file!("$CARGO_MANIFEST_DIR/tests/fixtures/text/windows.ini");
+static NORMALIZED: File<'_> = include_file!(
+    "$CARGO_MANIFEST_DIR/tests/fixtures/text/windows.ini",
+    strip_bom = true,
+    normalize_line_endings = true,
+    trim_trailing_whitespace = true,
+    text = true,
+);
+static TREE: Dir<'_> = include_dir!(
+    "$CARGO_MANIFEST_DIR/tests/fixtures",
+    strip_bom = "text/*.ini",
+    normalize_line_endings = ["text/*.ini"],
+    text = ["**/*.ini", "**/*.json"],
+);
+
+#[test]
+fn files_are_embedded_as_is_by_default() {
+    assert_eq!(
+        RAW.contents(),
+        b"\xEF\xBB\xBFHello,  \r\nWorld!\t\r\n".as_slice()
+    );
+    // it is still valid UTF-8
+    assert!(RAW.is_text());
+}
+
+#[test]
+fn normalize_a_windows_file() {
+    assert_eq!(NORMALIZED.contents_str(), "Hello,\nWorld!\n");
+    assert!(NORMALIZED.is_text());
+}
+
+#[test]
+fn transforms_only_apply_to_matching_files() {
+    let windows = TREE.get_file("text/windows.ini").unwrap();
+    assert_eq!(windows.contents_str(), "Hello,  \nWorld!\t\n");
+
+    let json = TREE.get_file("data/server.json").unwrap();
+    assert!(json.is_text());
+    assert_eq!(json.contents_str().as_bytes(), json.contents());
+
+    let binary = TREE.get_file("text/binary.bin").unwrap();
+    assert!(!binary.is_text());
+}
+
+#[test]
+fn utf8_files_are_detected_at_compile_time() {
+    for file in TREE.walk().filter_map(|e| e.as_file()) {
+        let valid = std::str::from_utf8(file.contents()).ok();
+
+        assert_eq!(file.is_text(), valid.is_some(), "{}", file.path().display());
+        assert_eq!(file.as_str(), valid);
+        assert_eq!(file.contents_utf8(), valid);
+    }
+
+    // hand-written files aren't checked
+    let file = File::new("hello.txt", b"Hello, World!");
+    assert_eq!(file.as_str(), None);
+    assert_eq!(file.contents_utf8(), Some("Hello, World!"));
+    assert_eq!(file, File::new_text("hello.txt", "Hello, World!"));
+}
+
+#[test]
+#[should_panic(expected = "wasn't embedded as text")]
+fn only_text_files_have_infallible_contents() {
+    let _ = TREE.get_file("text/binary.bin").unwrap().contents_str();
+}
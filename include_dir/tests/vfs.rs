@@ -0,0 +1,59 @@
+#![cfg(feature = "vfs")]
+
+use include_dir::{include_dir, Dir};
+use std::io::Read;
+use vfs::{VfsFileType, VfsPath};
+
+static PARENT_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR");
+
+fn root() -> VfsPath {
+    VfsPath::new(PARENT_DIR.clone())
+}
+
+#[test]
+fn read_files_and_directories() {
+    let root = root();
+
+    let mut lib_rs = String::new();
+    root.join("src/lib.rs")
+        .unwrap()
+        .open_file()
+        .unwrap()
+        .read_to_string(&mut lib_rs)
+        .unwrap();
+    assert_eq!(lib_rs, include_str!("../src/lib.rs"));
+
+    let mut children: Vec<_> = root
+        .join("tests")
+        .unwrap()
+        .read_dir()
+        .unwrap()
+        .map(|p| p.filename())
+        .collect();
+    children.sort();
+    assert!(children.contains(&"vfs.rs".to_string()));
+
+    assert!(root.join("Cargo.toml").unwrap().exists().unwrap());
+    assert!(!root.join("missing.txt").unwrap().exists().unwrap());
+}
+
+#[test]
+fn metadata() {
+    let root = root();
+
+    let cargo_toml = root.join("Cargo.toml").unwrap().metadata().unwrap();
+    assert_eq!(cargo_toml.file_type, VfsFileType::File);
+    assert_eq!(cargo_toml.len, include_bytes!("../Cargo.toml").len() as u64);
+
+    let src = root.join("src").unwrap().metadata().unwrap();
+    assert_eq!(src.file_type, VfsFileType::Directory);
+}
+
+#[test]
+fn the_filesystem_is_read_only() {
+    let root = root();
+
+    assert!(root.join("new.txt").unwrap().create_file().is_err());
+    assert!(root.join("new").unwrap().create_dir().is_err());
+    assert!(root.join("Cargo.toml").unwrap().remove_file().is_err());
+}
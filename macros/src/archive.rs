@@ -0,0 +1,146 @@
+//! Serializing a directory tree using the archive format understood by
+//! `include_dir::ArchiveDir`.
+//!
+//! See `include_dir/src/archive.rs` for a description of the format. The two
+//! implementations need to be kept in sync.
+
+use crate::{metadata::Reader, tree};
+
+const MAGIC: &[u8; 4] = b"IDAR";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 16;
+const RECORD_LEN: usize = 32;
+
+const FLAG_HASHES: u16 = 1;
+const FLAG_METADATA: u16 = 2;
+
+const KIND_DIR: u8 = 0;
+const KIND_FILE: u8 = 1;
+
+/// Serialize a directory tree as an archive.
+pub(crate) fn to_archive_bytes(metadata: &Reader, root: &tree::Dir) -> Vec<u8> {
+    // lay the entries out breadth-first, remembering where each directory's
+    // children start
+    let mut entries: Vec<&tree::Entry> = root.children.values().collect();
+    let mut children = Vec::new();
+    let mut i = 0;
+
+    while i < entries.len() {
+        let range = match entries[i] {
+            tree::Entry::Dir(d) => {
+                let first = entries.len();
+                entries.extend(d.children.values());
+                (first as u64, d.children.len() as u64)
+            }
+            tree::Entry::File(_) => (0, 0),
+        };
+        children.push(range);
+        i += 1;
+    }
+
+    let with_metadata = metadata.is_enabled();
+    let mut flags = FLAG_HASHES;
+    let mut record_len = RECORD_LEN + 8;
+    if with_metadata {
+        flags |= FLAG_METADATA;
+        record_len += crate::metadata::ARCHIVE_LEN;
+    }
+
+    let mut header = Vec::with_capacity(HEADER_LEN + entries.len() * record_len);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&flags.to_le_bytes());
+    header.extend_from_slice(&(root.children.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+
+    let mut body = Vec::new();
+    let body_start = (HEADER_LEN + entries.len() * record_len) as u64;
+
+    for (entry, (first, len)) in entries.iter().zip(children) {
+        let path = entry.path();
+        let path_offset = body_start + body.len() as u64;
+        body.extend_from_slice(path.as_bytes());
+
+        let (kind, data_offset, len, hash) = match entry {
+            tree::Entry::Dir(_) => (KIND_DIR, first, len, 0),
+            tree::Entry::File(f) => {
+                let contents = f.read();
+                let offset = body_start + body.len() as u64;
+                body.extend_from_slice(&contents);
+                (KIND_FILE, offset, contents.len() as u64, fnv1a(&contents))
+            }
+        };
+        let entry_metadata = metadata.read(entry.source());
+
+        header.push(kind);
+        header.push(entry_metadata.is_some() as u8);
+        header.extend_from_slice(&[0; 2]);
+        header.extend_from_slice(&(path.len() as u32).to_le_bytes());
+        header.extend_from_slice(&path_offset.to_le_bytes());
+        header.extend_from_slice(&data_offset.to_le_bytes());
+        header.extend_from_slice(&len.to_le_bytes());
+        header.extend_from_slice(&hash.to_le_bytes());
+
+        if with_metadata {
+            let block = entry_metadata.map(|m| m.to_archive_bytes());
+            header.extend_from_slice(&block.unwrap_or([0; crate::metadata::ARCHIVE_LEN]));
+        }
+    }
+
+    header.extend(body);
+    header
+}
+
+/// The 64-bit FNV-1a hash of some bytes.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hasher = Fnv1a::default();
+    hasher.update(bytes);
+    hasher.0
+}
+
+/// Calculates the 64-bit FNV-1a hash of everything written to it, so a file
+/// can be hashed using [`std::io::copy()`] without reading all of it into
+/// memory.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Fnv1a(pub(crate) u64);
+
+impl Fnv1a {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0 = bytes.iter().fold(self.0, |hash, b| {
+            (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+        });
+    }
+}
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl std::io::Write for Fnv1a {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_test_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+
+        let mut hasher = Fnv1a::default();
+        std::io::copy(&mut &b"foobar"[..], &mut hasher).unwrap();
+        assert_eq!(hasher.0, 0x8594_4171_f739_67e8);
+    }
+}
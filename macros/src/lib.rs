@@ -1,46 +1,330 @@
 //! Implementation details of the `include_dir`.
 //!
 //! You probably don't want to use this crate directly.
-#![cfg_attr(feature = "nightly", feature(track_path, proc_macro_tracked_env))]
-
-use proc_macro::{TokenStream, TokenTree};
-use proc_macro2::Literal;
-use quote::quote;
+#![cfg_attr(
+    feature = "nightly",
+    feature(track_path, proc_macro_tracked_env, proc_macro_diagnostic)
+)]
+
+mod archive;
+mod limits;
+#[cfg(feature = "fluent")]
+mod locales;
+mod metadata;
+mod migrations;
+mod options;
+mod transform;
+mod tree;
+mod validate;
+
+use crate::options::{Mount, NonUtf8Paths, Options};
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span};
+use quote::{quote, quote_spanned};
 use std::{
-    error::Error,
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    error::Error as StdError,
     fmt::{self, Display, Formatter},
     path::{Path, PathBuf},
-    time::SystemTime,
 };
 
 /// Embed the contents of a directory in your crate.
+///
+/// The path may be followed by a list of `key = value` options, grouped
+/// below by what they affect.
+///
+/// # Paths
+///
+/// - `deny_case_collisions = true` - fail to compile if two paths only differ
+///   by case (e.g. `Logo.png` and `logo.png`), because they would clobber each
+///   other on a case-insensitive filesystem
+/// - `non_utf8_paths = "error"` - what to do when a path isn't valid UTF-8.
+///   Use `"lossy"` to replace invalid sequences with `U+FFFD`, otherwise the
+///   path is a compile error. Either way, it is an error for two paths to end
+///   up the same after conversion
+/// - `normalize_unicode = false` - convert every embedded path to Unicode
+///   Normalization Form C, so files with accented names authored on macOS
+///   can be looked up using the composed form. Requires the `nfc` feature
+/// - `on_conflict = "error"` - what to do when several directories are
+///   mounted and more than one of them provides the same path. Use `"first"`
+///   or `"last"` to keep the entry from the directory declared first or last
+///
+/// Instead of a single path, you can also pass a map of mount points to
+/// directories to combine several directories into one tree:
+///
+/// ```rust,ignore
+/// static ASSETS: Dir<'_> = include_dir!({
+///     "" => "$CARGO_MANIFEST_DIR/static",
+///     "vendor" => "$OUT_DIR/vendor",
+/// });
+/// ```
+///
+/// # Transforms
+///
+/// - `strip_bom = false` - remove the UTF-8 byte order mark from the start
+///   of matching files
+/// - `normalize_line_endings = false` - convert `\r\n` line endings to `\n`
+///   in matching files
+/// - `trim_trailing_whitespace = false` - remove spaces and tabs from the end
+///   of every line in matching files
+/// - `text = false` - fail to compile if a matching file isn't valid UTF-8.
+///   Every file which is valid UTF-8 is embedded as text (using
+///   `include_str!()`), so `File::as_str()` doesn't need to check it again
+///
+/// These options apply to every file when set to `true`, or can be given a
+/// glob pattern or list of patterns which are matched against each file's
+/// path, e.g. `text = ["**/*.html", "**/*.txt"]`. They are applied in the
+/// order listed, after the size limits are checked and before any other
+/// validation runs. Files which aren't valid UTF-8 are never transformed, so
+/// binary files are embedded as-is.
+///
+/// # Validation
+///
+/// - `max_file_size = "10 MiB"` - fail to compile if any file is larger
+///   than this. The size can be a number of bytes or a string using `B`,
+///   `KB`, `MB`, `GB`, `KiB`, `MiB` or `GiB`. The error lists the largest
+///   offending files. Sizes are checked on disk, before any file is read or
+///   transformed
+/// - `max_total_size = "100 MB"` - fail to compile if all the files add up to
+///   more than this, listing the largest files
+/// - `max_files = 1000` - fail to compile if more than this many files would
+///   be embedded, listing the top-level directories with the most files
+/// - `size_report = false` - emit a warning summarising the number of files
+///   and bytes under each top-level directory, which is handy for keeping an
+///   eye on what ends up in the binary. On stable Rust the warning is
+///   reported as a use of a deprecated constant
+/// - `validate_syntax = true` - fail to compile if a `.json`, `.toml`, `.yaml`
+///   or `.yml` file can't be parsed. The `json`, `toml` or `yaml` feature
+///   must be enabled for each format that is embedded
+/// - `validate_migrations = true` - fail to compile if the directory isn't a
+///   valid set of SQL migrations (see `Dir::migrations()`), e.g. because two
+///   migrations have the same version or one is missing its `up.sql`
+/// - `validate_locales = true` - fail to compile if the sub-directories of a
+///   translations directory (one per locale) don't all contain the same
+///   `.ftl` files. This requires the `fluent` feature
+///
+/// # Code Generation
+///
+/// - `metadata = false` - embed filesystem metadata like modified times and
+///   permissions for each file and directory. This requires the `metadata`
+///   feature, which only controls whether the APIs for reading metadata are
+///   available, so other crates in the same build aren't affected
+/// - `metadata_accessed = true` - whether to include the time each entry was
+///   last accessed when using `metadata = true`. Reading a file usually
+///   updates its access time, so use `false` for reproducible builds
+/// - `metadata_modified = "filesystem"` - where each entry's modified time
+///   comes from when using `metadata = true`. Use `"git"` to take it
+///   from the last commit touching the file (or anything inside a directory),
+///   so it doesn't change with every checkout. Entries which aren't tracked by
+///   git keep their filesystem time
+/// - `fingerprint = false` - give every file a second path with a hash of
+///   its contents inserted before the extension (e.g. `app.3f9a1c0d.js` for
+///   `app.js`), available from `File::fingerprinted_path()`. Use
+///   `Dir::asset_url()` to get a file's URL and
+///   `Dir::get_by_fingerprinted_path()` to look it up again, so assets can
+///   be served with far-future caching
+/// - `align = 16` - start the contents of every file at an address which is
+///   a multiple of this many bytes, so they can be read in place as
+///   something like a `&[u32]` (see `File::contents_as()`). A
+///   `{ "pattern" => alignment }` map can be used to only align matching
+///   files, e.g. `align = { "**/*.mesh" => 16 }`. Aligned files are embedded
+///   as bytes, even if they are valid UTF-8
+///
+/// When using `metadata = true` and the [`SOURCE_DATE_EPOCH`][sde]
+/// environment variable is set, every timestamp is clamped so it is no later
+/// than that time and the owner's user and group IDs are left out, making the
+/// embedded metadata reproducible.
+///
+/// [sde]: https://reproducible-builds.org/docs/source-date-epoch/
 #[proc_macro]
 pub fn include_dir(input: TokenStream) -> TokenStream {
-    let tokens: Vec<_> = input.into_iter().collect();
+    match options::parse(input.into()).and_then(|options| expand(&options)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
 
-    let path = match tokens.as_slice() {
-        [TokenTree::Literal(lit)] => unwrap_string_literal(lit),
-        _ => panic!("This macro only accepts a single, non-empty string argument"),
-    };
+/// Embed a single file in your crate as an `include_dir::File`.
+///
+/// The file's path will be its name. This accepts the same arguments as
+/// `include_dir!()`.
+#[proc_macro]
+pub fn include_file(input: TokenStream) -> TokenStream {
+    match options::parse(input.into()).and_then(|options| expand_single_file(&options)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Embed the contents of a directory in your crate as a single archive,
+/// which can be loaded using `include_dir::ArchiveDir::from_bytes()`.
+///
+/// This accepts the same arguments as `include_dir!()`, and expands to a
+/// `&'static [u8]`. Because the whole tree is emitted as one byte string
+/// instead of a tree of `Dir` and `File` expressions, it is much cheaper for
+/// the compiler when embedding a large number of files.
+///
+/// ```rust,ignore
+/// static ASSETS: &[u8] = include_archive!("$CARGO_MANIFEST_DIR/assets");
+/// ```
+#[proc_macro]
+pub fn include_archive(input: TokenStream) -> TokenStream {
+    match options::parse(input.into()).and_then(|options| expand_archive(&options)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(options: &Options) -> Result<proc_macro2::TokenStream, Error> {
+    let root = build_tree(options)?;
+    let metadata = metadata::Reader::new(options)?;
+
+    let tokens = expand_dir(options, &metadata, &root)?;
+
+    Ok(with_size_report(options, &root, tokens))
+}
+
+fn expand_archive(options: &Options) -> Result<proc_macro2::TokenStream, Error> {
+    let root = build_tree(options)?;
+    let metadata = metadata::Reader::new(options)?;
+    let literal = Literal::byte_string(&archive::to_archive_bytes(&metadata, &root));
+
+    Ok(with_size_report(options, &root, quote!(#literal)))
+}
+
+/// Scan and merge every mount, then run any checks that were requested.
+fn build_tree(options: &Options) -> Result<tree::Dir, Error> {
+    let mut root: Option<tree::Dir> = None;
+
+    for (index, mount) in options.mounts.iter().enumerate() {
+        let path = resolve_path(&mount.path, get_env)
+            .map_err(|e| Error::new(mount.span, e.to_string()))?;
 
-    let path = resolve_path(&path, get_env).unwrap();
+        if !path.is_dir() {
+            return Err(Error::new(
+                mount.span,
+                format!("\"{}\" is not a directory", path.display()),
+            ));
+        }
+
+        let point = to_nfc(options, normalize_mount_point(mount)?);
+        let dir = scan_dir(options, index, &point, &path, &path)?.into_root();
+
+        match root {
+            Some(ref mut root) => root.merge(dir, options.on_conflict).map_err(|c| {
+                Error::new(
+                    options.mounts[c.mount].span,
+                    format!(
+                        "\"{}\" is provided by both \"{}\" and \"{}\"",
+                        c.path,
+                        c.existing.display(),
+                        c.replacement.display()
+                    ),
+                )
+            })?,
+            None => root = Some(dir),
+        }
+    }
+
+    let mut root = root.expect("The parser guarantees there is at least one mount");
+
+    limits::check_limits(options, &root)?;
+    transform::transform(options, &mut root)?;
+
+    if options.deny_case_collisions {
+        check_case_collisions(options, &root)?;
+    }
+
+    if options.validate_syntax {
+        validate::validate_syntax(options, &root)?;
+    }
 
-    expand_dir(&path, &path).into()
+    if options.validate_migrations {
+        migrations::validate_migrations(options, &root)?;
+    }
+
+    #[cfg(feature = "fluent")]
+    if options.validate_locales {
+        locales::validate_locales(options, &root)?;
+    }
+
+    Ok(root)
 }
 
-fn unwrap_string_literal(lit: &proc_macro::Literal) -> String {
-    let mut repr = lit.to_string();
-    if !repr.starts_with('"') || !repr.ends_with('"') {
-        panic!("This macro only accepts a single, non-empty string argument")
+fn expand_single_file(options: &Options) -> Result<proc_macro2::TokenStream, Error> {
+    let mount = match options.mounts.as_slice() {
+        [mount] if mount.point.is_empty() => mount,
+        _ => {
+            return Err(Error::new(
+                options.span,
+                "include_file!() expects a single path",
+            ))
+        }
+    };
+
+    let path =
+        resolve_path(&mount.path, get_env).map_err(|e| Error::new(mount.span, e.to_string()))?;
+
+    if !path.is_file() {
+        return Err(Error::new(
+            mount.span,
+            format!("\"{}\" is not a file", path.display()),
+        ));
     }
 
-    repr.remove(0);
-    repr.pop();
+    let root = path.parent().unwrap_or_else(|| Path::new(""));
+    let file = tree::File {
+        path: normalize_path(options, root, &path)?,
+        source: path.clone(),
+        mount: 0,
+        contents: None,
+        text: false,
+    };
+
+    let mut root = tree::Dir {
+        path: String::new(),
+        source: root.to_path_buf(),
+        mount: 0,
+        children: BTreeMap::new(),
+    };
+    root.children
+        .insert(file.path.clone(), tree::Entry::File(file));
+    limits::check_limits(options, &root)?;
+    transform::transform(options, &mut root)?;
+
+    let file = match root.children.values().next() {
+        Some(tree::Entry::File(f)) => f,
+        _ => unreachable!("The root only contains the file"),
+    };
+
+    let metadata = metadata::Reader::new(options)?;
+    let tokens = expand_file(options, &metadata, file)?;
+
+    Ok(with_size_report(options, &root, tokens))
+}
 
-    repr
+/// Add a summary of what is being embedded if `size_report = true`.
+fn with_size_report(
+    options: &Options,
+    root: &tree::Dir,
+    tokens: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if options.size_report {
+        limits::with_size_report(options, root, tokens)
+    } else {
+        tokens
+    }
 }
 
-fn expand_dir(root: &Path, path: &Path) -> proc_macro2::TokenStream {
+/// Read a directory from disk, placing its contents under the mount point.
+fn scan_dir(
+    options: &Options,
+    mount: usize,
+    point: &str,
+    root: &Path,
+    path: &Path,
+) -> Result<tree::Dir, Error> {
     let children = read_dir(path).unwrap_or_else(|e| {
         panic!(
             "Unable to read the entries in \"{}\": {}",
@@ -49,94 +333,302 @@ fn expand_dir(root: &Path, path: &Path) -> proc_macro2::TokenStream {
         )
     });
 
-    let mut child_tokens = Vec::new();
+    let mut dir = tree::Dir {
+        path: join_paths(point, &normalize_path(options, root, path)?),
+        source: path.to_path_buf(),
+        mount,
+        children: BTreeMap::new(),
+    };
 
     for child in children {
-        if child.is_dir() {
-            let tokens = expand_dir(root, &child);
-            child_tokens.push(quote! {
-                include_dir::DirEntry::Dir(#tokens)
-            });
+        let entry = if child.is_dir() {
+            tree::Entry::Dir(scan_dir(options, mount, point, root, &child)?)
         } else if child.is_file() {
-            let tokens = expand_file(root, &child);
-            child_tokens.push(quote! {
-                include_dir::DirEntry::File(#tokens)
-            });
+            tree::Entry::File(tree::File {
+                path: join_paths(point, &normalize_path(options, root, &child)?),
+                source: child,
+                mount,
+                contents: None,
+                text: false,
+            })
         } else {
             panic!("\"{}\" is neither a file nor a directory", child.display());
+        };
+
+        insert_child(options, &mut dir, entry)?;
+    }
+
+    Ok(dir)
+}
+
+/// Add an entry to a directory, making sure it doesn't clobber another entry
+/// which ended up with the same path (e.g. after lossy UTF-8 conversion or
+/// Unicode normalization).
+fn insert_child(options: &Options, dir: &mut tree::Dir, entry: tree::Entry) -> Result<(), Error> {
+    let name = entry
+        .path()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    if let Some(previous) = dir.children.get(&name) {
+        return Err(Error::new(
+            options.mounts[entry.mount()].span,
+            format!(
+                "{:?} and {:?} would both be embedded as \"{}\"",
+                previous.source(),
+                entry.source(),
+                entry.path(),
+            ),
+        ));
+    }
+
+    dir.children.insert(name, entry);
+
+    Ok(())
+}
+
+/// Make sure no two entries in a directory have paths which only differ by
+/// case, because they would clobber each other on a case-insensitive
+/// filesystem.
+fn check_case_collisions(options: &Options, dir: &tree::Dir) -> Result<(), Error> {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+
+    for child in dir.children.values() {
+        if let Some(previous) = seen.insert(child.path().to_lowercase(), child.path()) {
+            return Err(Error::new(
+                options.span,
+                format!(
+                    "\"{}\" and \"{}\" only differ by case",
+                    previous,
+                    child.path(),
+                ),
+            ));
+        }
+
+        if let tree::Entry::Dir(d) = child {
+            check_case_collisions(options, d)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn expand_dir(
+    options: &Options,
+    metadata: &metadata::Reader,
+    dir: &tree::Dir,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let mut child_tokens = Vec::new();
+
+    for child in dir.children.values() {
+        match child {
+            tree::Entry::Dir(d) => {
+                let tokens = expand_dir(options, metadata, d)?;
+                child_tokens.push(quote! {
+                    include_dir::DirEntry::Dir(#tokens)
+                });
+            }
+            tree::Entry::File(f) => {
+                let tokens = expand_file(options, metadata, f)?;
+                child_tokens.push(quote! {
+                    include_dir::DirEntry::File(#tokens)
+                });
+            }
         }
     }
 
-    let path = normalize_path(root, path);
+    let path = &dir.path;
+    let (file_count, total_size) = dir.stats();
 
-    quote! {
+    let tokens = quote! {
         include_dir::Dir::new(#path, {
             const ENTRIES: &'static [include_dir::DirEntry<'static>] = &[ #(#child_tokens),*];
             ENTRIES
-    })
-    }
+        })
+        .with_stats(#file_count, #total_size)
+    };
+
+    Ok(with_metadata(metadata, tokens, &dir.source))
 }
 
-fn expand_file(root: &Path, path: &Path) -> proc_macro2::TokenStream {
+fn expand_file(
+    options: &Options,
+    metadata: &metadata::Reader,
+    file: &tree::File,
+) -> Result<proc_macro2::TokenStream, Error> {
+    let path = &file.source;
     let abs = path
         .canonicalize()
         .unwrap_or_else(|e| panic!("failed to resolve \"{}\": {}", path.display(), e));
-    let literal = match abs.to_str() {
-        Some(abs) => quote!(include_bytes!(#abs)),
-        None => {
-            let contents = read_file(path);
-            let literal = Literal::byte_string(&contents);
-            quote!(#literal)
+    // aligned files are always embedded as bytes
+    let align = options.align.for_file(&file.path);
+    let text = file.text && align.is_none();
+
+    let literal = match (abs.to_str(), &file.contents) {
+        (Some(abs), None) if text => quote!(include_str!(#abs)),
+        (Some(abs), None) => quote!(include_bytes!(#abs)),
+        (abs, _) => {
+            let contents = file.read();
+            let literal = match std::str::from_utf8(&contents) {
+                Ok(s) if text => Literal::string(s),
+                _ => Literal::byte_string(&contents),
+            };
+
+            match abs {
+                // keep depending on the original file so editing it still
+                // triggers a rebuild
+                Some(abs) => quote!({
+                    const _: &[u8] = include_bytes!(#abs);
+                    #literal
+                }),
+                None => quote!(#literal),
+            }
         }
     };
 
-    let normalized_path = normalize_path(root, path);
+    let literal = match align {
+        Some(align) => {
+            let align = Literal::u64_unsuffixed(align);
+            let len = file.len() as usize;
+
+            quote!({
+                #[repr(C, align(#align))]
+                struct Aligned<T>(T);
+                const ALIGNED: &Aligned<[u8; #len]> = &Aligned(*#literal);
+                &ALIGNED.0
+            })
+        }
+        None => literal,
+    };
 
-    let tokens = quote! {
-        include_dir::File::new(#normalized_path, #literal)
+    let normalized_path = &file.path;
+    let constructor = if text { quote!(new_text) } else { quote!(new) };
+
+    let mut tokens = quote! {
+        include_dir::File::#constructor(#normalized_path, #literal)
     };
 
-    match metadata(path) {
-        Some(metadata) => quote!(#tokens.with_metadata(#metadata)),
-        None => tokens,
+    // only fingerprinting needs the hash up front, so don't read every file
+    // just to hash it. File::hash() computes it on demand instead.
+    if options.fingerprint {
+        let hash = file.hash();
+        let fingerprinted = fingerprinted_path(normalized_path, hash);
+        tokens = quote!(#tokens.with_hash(#hash).with_fingerprinted_path(#fingerprinted));
     }
+
+    Ok(with_metadata(metadata, tokens, path))
 }
 
-fn metadata(path: &Path) -> Option<proc_macro2::TokenStream> {
-    fn to_unix(t: SystemTime) -> u64 {
-        t.duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+/// Insert a hash of a file's contents before its extension, turning
+/// `js/app.js` into `js/app.3f9a1c0d.js`.
+fn fingerprinted_path(path: &str, hash: u64) -> String {
+    let hash = format!("{:016x}", hash);
+    let hash = &hash[..8];
+
+    let name_start = path.rfind('/').map_or(0, |ix| ix + 1);
+    // dotfiles like ".htaccess" don't have an extension
+    match path[name_start..].rfind('.') {
+        Some(ix) if ix > 0 => {
+            let ix = name_start + ix;
+            format!("{}.{}{}", &path[..ix], hash, &path[ix..])
+        }
+        _ => format!("{}.{}", path, hash),
     }
+}
 
-    if !cfg!(feature = "metadata") {
-        return None;
+/// Attach an entry's metadata to the expression that constructs it, if
+/// metadata was requested.
+pub(crate) fn with_metadata(
+    metadata: &metadata::Reader,
+    tokens: proc_macro2::TokenStream,
+    source: &Path,
+) -> proc_macro2::TokenStream {
+    match metadata.read(source) {
+        Some(metadata) => {
+            let metadata = metadata.to_tokens();
+            quote!(#tokens.with_metadata(#metadata))
+        }
+        None => tokens,
     }
-
-    let meta = path.metadata().ok()?;
-    let accessed = meta.accessed().map(to_unix).ok()?;
-    let created = meta.created().map(to_unix).ok()?;
-    let modified = meta.modified().map(to_unix).ok()?;
-
-    Some(quote! {
-        include_dir::Metadata::new(
-            std::time::Duration::from_secs(#accessed),
-            std::time::Duration::from_secs(#created),
-            std::time::Duration::from_secs(#modified),
-        )
-    })
 }
 
 /// Make sure that paths use the same separator regardless of whether the host
 /// machine is Windows or Linux.
-fn normalize_path(root: &Path, path: &Path) -> String {
+///
+/// Paths which aren't valid UTF-8 are handled according to the
+/// `non_utf8_paths` option, and are converted to Unicode Normalization Form C
+/// when using `normalize_unicode = true`.
+fn normalize_path(options: &Options, root: &Path, path: &Path) -> Result<String, Error> {
     let stripped = path
         .strip_prefix(root)
         .expect("Should only ever be called using paths inside the root path");
-    let as_string = stripped.to_string_lossy();
 
-    as_string.replace('\\', "/")
+    let as_string = match stripped.to_str() {
+        Some(s) => Cow::Borrowed(s),
+        None => match options.non_utf8_paths {
+            NonUtf8Paths::Lossy => stripped.to_string_lossy(),
+            NonUtf8Paths::Error => {
+                return Err(Error::new(
+                    options.span,
+                    format!(
+                    "{:?} is not valid UTF-8 (use `non_utf8_paths = \"lossy\"` to embed it anyway)",
+                    stripped
+                ),
+                ))
+            }
+        },
+    };
+
+    let normalized = as_string.replace('\\', "/");
+
+    Ok(to_nfc(options, normalized))
+}
+
+/// Normalize a mount point the same way as the paths being mounted.
+fn normalize_mount_point(mount: &Mount) -> Result<String, Error> {
+    let mut components = Vec::new();
+
+    for component in mount.point.split(['/', '\\']) {
+        match component {
+            "" | "." => {}
+            ".." => {
+                return Err(Error::new(
+                    mount.span,
+                    format!("The mount point \"{}\" can't contain \"..\"", mount.point),
+                ))
+            }
+            other => components.push(other),
+        }
+    }
+
+    Ok(components.join("/"))
+}
+
+#[cfg(feature = "nfc")]
+fn to_nfc(options: &Options, path: String) -> String {
+    if options.normalize_unicode {
+        unicode_normalization::UnicodeNormalization::nfc(path.as_str()).collect()
+    } else {
+        path
+    }
+}
+
+#[cfg(not(feature = "nfc"))]
+fn to_nfc(_options: &Options, path: String) -> String {
+    path
 }
 
-fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+fn join_paths(parent: &str, child: &str) -> String {
+    match (parent.is_empty(), child.is_empty()) {
+        (true, _) => child.to_string(),
+        (false, true) => parent.to_string(),
+        (false, false) => format!("{}/{}", parent, child),
+    }
+}
+
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn StdError>> {
     if !dir.is_dir() {
         panic!("\"{}\" is not a directory", dir.display());
     }
@@ -155,15 +647,15 @@ fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
     Ok(paths)
 }
 
-fn read_file(path: &Path) -> Vec<u8> {
+pub(crate) fn read_file(path: &Path) -> Vec<u8> {
     track_path(path);
     std::fs::read(path).unwrap_or_else(|e| panic!("Unable to read \"{}\": {}", path.display(), e))
 }
 
-fn resolve_path(
+pub(crate) fn resolve_path(
     raw: &str,
     get_env: impl Fn(&str) -> Option<String>,
-) -> Result<PathBuf, Box<dyn Error>> {
+) -> Result<PathBuf, Box<dyn StdError>> {
     let mut unprocessed = raw;
     let mut resolved = String::new();
 
@@ -189,12 +681,33 @@ fn resolve_path(
     Ok(PathBuf::from(resolved))
 }
 
+/// An error that should be reported to the user as a `compile_error!()`.
+#[derive(Debug, Clone)]
+struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Error {
+            span,
+            message: message.into(),
+        }
+    }
+
+    fn to_compile_error(&self) -> proc_macro2::TokenStream {
+        let Error { span, message } = self;
+        quote_spanned!(*span=> compile_error!(#message))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct MissingVariable {
     variable: String,
 }
 
-impl Error for MissingVariable {}
+impl StdError for MissingVariable {}
 
 impl Display for MissingVariable {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -207,7 +720,7 @@ struct UnableToParseVariable {
     rest: String,
 }
 
-impl Error for UnableToParseVariable {}
+impl StdError for UnableToParseVariable {}
 
 impl Display for UnableToParseVariable {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -251,12 +764,12 @@ fn take_while(s: &str, mut predicate: impl FnMut(char) -> bool) -> (&str, &str)
 }
 
 #[cfg(feature = "nightly")]
-fn get_env(variable: &str) -> Option<String> {
+pub(crate) fn get_env(variable: &str) -> Option<String> {
     proc_macro::tracked_env::var(variable).ok()
 }
 
 #[cfg(not(feature = "nightly"))]
-fn get_env(variable: &str) -> Option<String> {
+pub(crate) fn get_env(variable: &str) -> Option<String> {
     std::env::var(variable).ok()
 }
 
@@ -337,6 +850,123 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fingerprints_go_before_the_extension() {
+        let hash = &format!("{:016x}", archive::fnv1a(b"console.log()"))[..8];
+        let fingerprint = |path| fingerprinted_path(path, archive::fnv1a(b"console.log()"));
+
+        assert_eq!(fingerprint("app.js"), format!("app.{}.js", hash));
+        assert_eq!(
+            fingerprint("js/app.min.js"),
+            format!("js/app.min.{}.js", hash)
+        );
+        assert_eq!(fingerprint("LICENSE"), format!("LICENSE.{}", hash));
+        assert_eq!(
+            fingerprint("a.b/.htaccess"),
+            format!("a.b/.htaccess.{}", hash)
+        );
+    }
+
+    /// Build the tree we would get if `children` were files in `root`.
+    fn files_in(options: &Options, root: &Path, children: &[PathBuf]) -> Result<tree::Dir, Error> {
+        let mut dir = tree::Dir {
+            path: String::new(),
+            source: root.to_path_buf(),
+            mount: 0,
+            children: BTreeMap::new(),
+        };
+
+        for child in children {
+            let file = tree::File {
+                path: normalize_path(options, root, child)?,
+                source: child.clone(),
+                mount: 0,
+                contents: None,
+                text: false,
+            };
+            insert_child(options, &mut dir, tree::Entry::File(file))?;
+        }
+
+        Ok(dir)
+    }
+
+    #[test]
+    fn detect_case_collisions() {
+        let root = Path::new("assets");
+        let children = [root.join("Logo.png"), root.join("logo.png")];
+        let options = options::parse(quote!("assets")).unwrap();
+        let dir = files_in(&options, root, &children).unwrap();
+
+        let err = check_case_collisions(&options, &dir).unwrap_err();
+
+        assert_eq!(
+            err.message,
+            "\"Logo.png\" and \"logo.png\" only differ by case"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn non_utf8_paths() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let root = Path::new("assets");
+        let children = [
+            root.join(OsStr::from_bytes(b"caf\xe9")),
+            root.join(OsStr::from_bytes(b"caf\xff")),
+        ];
+
+        let options = options::parse(quote!("assets")).unwrap();
+        assert!(normalize_path(&options, root, &children[0]).is_err());
+
+        let options = options::parse(quote!("assets", non_utf8_paths = "lossy")).unwrap();
+        assert_eq!(
+            normalize_path(&options, root, &children[0]).unwrap(),
+            "caf\u{fffd}"
+        );
+        let err = files_in(&options, root, &children).unwrap_err();
+        assert!(err.message.contains("would both be embedded as"));
+    }
+
+    #[test]
+    #[cfg(feature = "nfc")]
+    fn paths_are_normalized_to_nfc() {
+        let root = Path::new("assets");
+        let nfd = root.join("cafe\u{301}.txt");
+        let nfc = root.join("caf\u{e9}.txt");
+        let options = options::parse(quote!("assets", normalize_unicode = true)).unwrap();
+
+        assert_eq!(
+            normalize_path(&options, root, &nfd).unwrap(),
+            "caf\u{e9}.txt"
+        );
+        assert!(files_in(&options, root, &[nfc.clone(), nfd.clone()]).is_err());
+
+        let options = options::parse(quote!("assets")).unwrap();
+        assert_eq!(
+            normalize_path(&options, root, &nfd).unwrap(),
+            "cafe\u{301}.txt"
+        );
+        assert!(files_in(&options, root, &[nfc, nfd]).is_ok());
+    }
+
+    #[test]
+    fn normalize_mount_points() {
+        let options = options::parse(quote!({
+            "" => "a",
+            "/vendor\\js/" => "b",
+            "./x/../y" => "c",
+        }))
+        .unwrap();
+
+        assert_eq!(normalize_mount_point(&options.mounts[0]).unwrap(), "");
+        assert_eq!(
+            normalize_mount_point(&options.mounts[1]).unwrap(),
+            "vendor/js"
+        );
+        assert!(normalize_mount_point(&options.mounts[2]).is_err());
+    }
+
     #[test]
     fn invalid_variables() {
         let inputs = &["$1", "$"];
@@ -0,0 +1,418 @@
+//! Guarding against accidentally embedding huge amounts of data using the
+//! `max_file_size`, `max_total_size` and `max_files` options, and reporting
+//! how much is being embedded when using `size_report = true`.
+
+use crate::{options::Options, tree, Error};
+use proc_macro2::TokenStream;
+#[cfg(not(feature = "nightly"))]
+use quote::quote_spanned;
+use std::collections::BTreeMap;
+
+/// How many of the largest offenders to mention in an error message.
+const MAX_OFFENDERS: usize = 5;
+
+/// A file that will be embedded, along with its size on disk.
+#[derive(Debug, Clone, PartialEq)]
+struct SizedFile<'a> {
+    path: &'a str,
+    len: u64,
+}
+
+/// Make sure the tree is within every limit that was set.
+///
+/// This runs before any file is read, so sizes come from the filesystem's
+/// metadata rather than the (possibly transformed) contents.
+pub(crate) fn check_limits(options: &Options, root: &tree::Dir) -> Result<(), Error> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files);
+    // largest first, so error messages can list the worst offenders
+    files.sort_by(|a, b| b.len.cmp(&a.len).then(a.path.cmp(b.path)));
+
+    if let Some(limit) = options.max_file_size {
+        let too_big: Vec<_> = files.iter().filter(|f| f.len > limit.max).collect();
+
+        if !too_big.is_empty() {
+            return Err(Error::new(
+                limit.span,
+                format!(
+                    "{} {} larger than the {} limit: {}",
+                    too_big.len(),
+                    if too_big.len() == 1 {
+                        "file is"
+                    } else {
+                        "files are"
+                    },
+                    format_size(limit.max),
+                    list_files(&too_big),
+                ),
+            ));
+        }
+    }
+
+    if let Some(limit) = options.max_total_size {
+        let total: u64 = files.iter().map(|f| f.len).sum();
+
+        if total > limit.max {
+            let largest: Vec<_> = files.iter().collect();
+            return Err(Error::new(
+                limit.span,
+                format!(
+                    "The embedded files add up to {}, which is more than the {} limit. The largest are {}",
+                    format_size(total),
+                    format_size(limit.max),
+                    list_files(&largest),
+                ),
+            ));
+        }
+    }
+
+    if let Some(limit) = options.max_files {
+        if files.len() as u64 > limit.max {
+            let mut dirs: Vec<_> = summarise(root).into_iter().collect();
+            dirs.sort_by(|(a, x), (b, y)| y.files.cmp(&x.files).then(a.cmp(b)));
+
+            let busiest: Vec<_> = dirs
+                .iter()
+                .take(MAX_OFFENDERS)
+                .map(|(name, s)| format!("\"{}\" ({} files)", name, s.files))
+                .collect();
+
+            return Err(Error::new(
+                limit.span,
+                format!(
+                    "{} files would be embedded, which is more than the limit of {}. The most are in {}",
+                    files.len(),
+                    limit.max,
+                    busiest.join(", "),
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Emit a warning summarising the number of files and bytes under each
+/// top-level directory.
+///
+/// With the `nightly` feature this is a normal compiler warning.
+#[cfg(feature = "nightly")]
+pub(crate) fn with_size_report(
+    options: &Options,
+    root: &tree::Dir,
+    tokens: TokenStream,
+) -> TokenStream {
+    proc_macro::Diagnostic::spanned(
+        options.span.unwrap(),
+        proc_macro::Level::Warning,
+        size_report(root),
+    )
+    .emit();
+
+    tokens
+}
+
+/// Emit a warning summarising the number of files and bytes under each
+/// top-level directory.
+///
+/// Procedural macros can't emit warnings on stable Rust, so the expanded
+/// tokens are wrapped in a block which uses a `#[deprecated]` constant whose
+/// note is the summary.
+#[cfg(not(feature = "nightly"))]
+pub(crate) fn with_size_report(
+    options: &Options,
+    root: &tree::Dir,
+    tokens: TokenStream,
+) -> TokenStream {
+    let note = size_report(root);
+
+    quote_spanned! {options.span=>
+        {
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const include_dir_size_report: () = ();
+            #[allow(clippy::let_unit_value)]
+            let _ = include_dir_size_report;
+            #tokens
+        }
+    }
+}
+
+/// The text of the `size_report` warning, e.g.
+///
+/// ```text
+/// embedding 3 files, 1.5 KiB
+///   .: 1 file, 512 B
+///   css: 2 files, 1.0 KiB
+/// ```
+fn size_report(root: &tree::Dir) -> String {
+    let summary = summarise(root);
+    let total = summary.values().fold(Summary::default(), |acc, s| Summary {
+        files: acc.files + s.files,
+        bytes: acc.bytes + s.bytes,
+    });
+
+    let mut report = format!("embedding {}", total);
+    for (name, s) in &summary {
+        report.push_str(&format!("\n  {}: {}", name, s));
+    }
+
+    report
+}
+
+/// The number of files and bytes in part of the tree.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+struct Summary {
+    files: u64,
+    bytes: u64,
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}, {}",
+            self.files,
+            if self.files == 1 { "file" } else { "files" },
+            format_size(self.bytes)
+        )
+    }
+}
+
+/// Group the files by top-level directory, where files directly in the root
+/// are grouped under `"."`.
+fn summarise(root: &tree::Dir) -> BTreeMap<String, Summary> {
+    let mut summary: BTreeMap<String, Summary> = BTreeMap::new();
+
+    for child in root.children.values() {
+        let mut files = Vec::new();
+        let name = match child {
+            tree::Entry::Dir(d) => {
+                collect_files(d, &mut files);
+                d.path.clone()
+            }
+            tree::Entry::File(f) => {
+                files.push(SizedFile::of(f));
+                String::from(".")
+            }
+        };
+
+        let entry = summary.entry(name).or_default();
+        for file in files {
+            entry.files += 1;
+            entry.bytes += file.len;
+        }
+    }
+
+    summary
+}
+
+fn collect_files<'a>(dir: &'a tree::Dir, files: &mut Vec<SizedFile<'a>>) {
+    for child in dir.children.values() {
+        match child {
+            tree::Entry::Dir(d) => collect_files(d, files),
+            tree::Entry::File(f) => files.push(SizedFile::of(f)),
+        }
+    }
+}
+
+impl<'a> SizedFile<'a> {
+    fn of(file: &'a tree::File) -> Self {
+        SizedFile {
+            path: &file.path,
+            len: file.len(),
+        }
+    }
+}
+
+/// List the first few files, mentioning how many were left out.
+fn list_files(files: &[&SizedFile<'_>]) -> String {
+    let mut listed: Vec<_> = files
+        .iter()
+        .take(MAX_OFFENDERS)
+        .map(|f| format!("\"{}\" ({})", f.path, format_size(f.len)))
+        .collect();
+
+    if files.len() > MAX_OFFENDERS {
+        listed.push(format!("and {} more", files.len() - MAX_OFFENDERS));
+    }
+
+    listed.join(", ")
+}
+
+/// Format a number of bytes using binary units (e.g. `1.5 MiB`).
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn fixtures() -> (Options, tree::Dir) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let path = path.to_str().unwrap();
+        let options = crate::options::parse(quote::quote!(#path)).unwrap();
+        let root = crate::build_tree(&options).unwrap();
+
+        (options, root)
+    }
+
+    #[test]
+    fn sizes_are_human_readable() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 << 30), "3.0 GiB");
+    }
+
+    #[test]
+    fn the_largest_files_are_listed_first() {
+        let (mut options, root) = fixtures();
+        options.max_file_size = Some(crate::options::Limit {
+            max: 1,
+            span: options.span,
+        });
+
+        let err = check_limits(&options, &root).unwrap_err();
+
+        assert!(err.message.contains("\"lib.rs\""), "{}", err.message);
+        assert!(err.message.ends_with(" more"), "{}", err.message);
+    }
+
+    #[test]
+    fn too_many_files() {
+        let (mut options, root) = fixtures();
+        options.max_files = Some(crate::options::Limit {
+            max: 1,
+            span: options.span,
+        });
+
+        let err = check_limits(&options, &root).unwrap_err();
+
+        assert!(
+            err.message.contains("more than the limit of 1"),
+            "{}",
+            err.message
+        );
+    }
+
+    #[test]
+    fn limits_are_checked_before_files_are_read() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../include_dir/tests/fixtures");
+        let path = path.to_str().unwrap();
+        // "text/binary.bin" isn't valid UTF-8, so reading it would fail
+        let options =
+            crate::options::parse(quote::quote!(#path, text = true, max_files = 1)).unwrap();
+
+        let err = crate::build_tree(&options).unwrap_err();
+
+        assert!(
+            err.message.contains("more than the limit of 1"),
+            "{}",
+            err.message
+        );
+    }
+
+    #[test]
+    fn limits_are_checked_before_a_single_file_is_read() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../include_dir/tests/fixtures/text/binary.bin");
+        let path = path.to_str().unwrap();
+        let options =
+            crate::options::parse(quote::quote!(#path, text = true, max_file_size = 1)).unwrap();
+
+        let err = crate::expand_single_file(&options).unwrap_err();
+
+        assert!(
+            err.message
+                .starts_with("1 file is larger than the 1 B limit"),
+            "{}",
+            err.message
+        );
+    }
+
+    #[test]
+    fn everything_is_within_generous_limits() {
+        let (mut options, root) = fixtures();
+        let limit = crate::options::Limit {
+            max: u64::MAX,
+            span: options.span,
+        };
+        options.max_file_size = Some(limit);
+        options.max_total_size = Some(limit);
+        options.max_files = Some(limit);
+
+        check_limits(&options, &root).unwrap();
+    }
+
+    fn file(path: &str, len: usize) -> tree::Entry {
+        tree::Entry::File(tree::File {
+            path: path.to_string(),
+            source: path.into(),
+            mount: 0,
+            contents: Some(vec![0; len]),
+            text: false,
+        })
+    }
+
+    fn dir(path: &str, children: Vec<tree::Entry>) -> tree::Dir {
+        tree::Dir {
+            path: path.to_string(),
+            source: path.into(),
+            mount: 0,
+            children: children
+                .into_iter()
+                .map(|e| (e.path().rsplit('/').next().unwrap().to_string(), e))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn size_report_summary() {
+        let root = dir(
+            "",
+            vec![
+                file("index.html", 512),
+                tree::Entry::Dir(dir(
+                    "css",
+                    vec![
+                        file("css/site.css", 1024),
+                        tree::Entry::Dir(dir("css/vendor", vec![file("css/vendor/a.css", 2048)])),
+                    ],
+                )),
+                tree::Entry::Dir(dir("img", vec![file("img/logo.png", 1)])),
+            ],
+        );
+
+        assert_eq!(
+            size_report(&root),
+            "embedding 4 files, 3.5 KiB\n  .: 1 file, 512 B\n  css: 2 files, 3.0 KiB\n  img: 1 file, 1 B"
+        );
+    }
+
+    #[test]
+    fn files_are_grouped_by_top_level_directory() {
+        let (_, root) = fixtures();
+
+        let summary = summarise(&root);
+
+        let files: u64 = summary.values().map(|s| s.files).sum();
+        assert_eq!(files, root.children.len() as u64);
+        assert_eq!(summary.keys().collect::<Vec<_>>(), ["."]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Checking that every locale in a translations directory has the same files
+//! when `validate_locales` is enabled.
+
+use crate::{options::Options, tree, Error};
+use std::collections::BTreeSet;
+use unic_langid::LanguageIdentifier;
+
+/// Make sure each sub-directory of the root (one per locale) contains the
+/// same set of `.ftl` files, so no locale is missing translations.
+///
+/// Only the files `LocaleDir` would load are compared, so sub-directories
+/// which aren't named after a language identifier and other kinds of files
+/// are ignored.
+pub(crate) fn validate_locales(options: &Options, root: &tree::Dir) -> Result<(), Error> {
+    let mut locales = root.children.values().filter_map(|child| match child {
+        tree::Entry::Dir(d) if is_locale(d) => Some(d),
+        _ => None,
+    });
+
+    let first = match locales.next() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+    let expected = relative_files(first);
+
+    for locale in locales {
+        let actual = relative_files(locale);
+
+        if let Some(missing) = expected.difference(&actual).next() {
+            return Err(Error::new(
+                options.mounts[locale.mount].span,
+                format!(
+                    "The \"{}\" locale is missing \"{}\", which exists in \"{}\"",
+                    locale.path, missing, first.path
+                ),
+            ));
+        }
+
+        if let Some(extra) = actual.difference(&expected).next() {
+            return Err(Error::new(
+                options.mounts[locale.mount].span,
+                format!(
+                    "The \"{}\" locale has \"{}\", which doesn't exist in \"{}\"",
+                    locale.path, extra, first.path
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_locale(dir: &tree::Dir) -> bool {
+    let name = dir.path.rsplit('/').next().unwrap_or(&dir.path);
+    name.parse::<LanguageIdentifier>().is_ok()
+}
+
+/// The paths of every `.ftl` file in a locale, relative to the locale's
+/// directory.
+fn relative_files(locale: &tree::Dir) -> BTreeSet<String> {
+    fn walk(dir: &tree::Dir, prefix_len: usize, files: &mut BTreeSet<String>) {
+        for child in dir.children.values() {
+            match child {
+                tree::Entry::Dir(d) => walk(d, prefix_len, files),
+                tree::Entry::File(f) if f.path.ends_with(".ftl") => {
+                    files.insert(f.path[prefix_len..].to_string());
+                }
+                tree::Entry::File(_) => {}
+            }
+        }
+    }
+
+    let mut files = BTreeSet::new();
+    walk(locale, locale.path.len() + 1, &mut files);
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    fn locale(name: &str, files: &[&str]) -> tree::Entry {
+        let children = files
+            .iter()
+            .map(|f| {
+                let file = tree::File {
+                    path: format!("{}/{}", name, f),
+                    source: PathBuf::from(f),
+                    mount: 0,
+                    contents: None,
+                    text: false,
+                };
+                (f.to_string(), tree::Entry::File(file))
+            })
+            .collect();
+
+        tree::Entry::Dir(tree::Dir {
+            path: name.to_string(),
+            source: PathBuf::from(name),
+            mount: 0,
+            children,
+        })
+    }
+
+    fn root(locales: Vec<tree::Entry>) -> tree::Dir {
+        tree::Dir {
+            path: String::new(),
+            source: PathBuf::new(),
+            mount: 0,
+            children: locales
+                .into_iter()
+                .map(|l| (l.path().to_string(), l))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn options() -> Options {
+        crate::options::parse(quote::quote!("locales")).unwrap()
+    }
+
+    #[test]
+    fn matching_locales() {
+        let root = root(vec![
+            locale("en", &["main.ftl", "errors.ftl"]),
+            locale("fr", &["main.ftl", "errors.ftl"]),
+        ]);
+
+        assert!(validate_locales(&options(), &root).is_ok());
+    }
+
+    #[test]
+    fn only_translations_are_compared() {
+        let root = root(vec![
+            locale("en", &["main.ftl", "README.md"]),
+            locale("fr", &["main.ftl"]),
+            locale("templates", &["index.html"]),
+        ]);
+
+        assert!(validate_locales(&options(), &root).is_ok());
+    }
+
+    #[test]
+    fn missing_and_extra_files() {
+        let missing = root(vec![
+            locale("en", &["main.ftl", "errors.ftl"]),
+            locale("fr", &["main.ftl"]),
+        ]);
+        let err = validate_locales(&options(), &missing).unwrap_err();
+        assert_eq!(
+            err.message,
+            "The \"fr\" locale is missing \"errors.ftl\", which exists in \"en\""
+        );
+
+        let extra = root(vec![
+            locale("en", &["main.ftl"]),
+            locale("fr", &["main.ftl", "errors.ftl"]),
+        ]);
+        let err = validate_locales(&options(), &extra).unwrap_err();
+        assert_eq!(
+            err.message,
+            "The \"fr\" locale has \"errors.ftl\", which doesn't exist in \"en\""
+        );
+    }
+}
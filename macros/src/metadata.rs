@@ -0,0 +1,384 @@
+//! Reading the filesystem metadata that gets embedded alongside files and
+//! directories when using the `metadata = true` option.
+
+use crate::{
+    options::{ModifiedTime, Options},
+    Error,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+const HAS_ACCESSED: u32 = 1;
+const HAS_CREATED: u32 = 2;
+const HAS_MODIFIED: u32 = 4;
+const HAS_MODE: u32 = 8;
+const HAS_OWNER: u32 = 16;
+
+/// The length of an entry's metadata in the archive format.
+pub(crate) const ARCHIVE_LEN: usize = 40;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Metadata {
+    accessed: Option<Duration>,
+    created: Option<Duration>,
+    modified: Option<Duration>,
+    len: u64,
+    mode: Option<u32>,
+    owner: Option<(u32, u32)>,
+}
+
+/// Reads the metadata for each entry, applying any options which make the
+/// result reproducible.
+#[derive(Debug, Default)]
+pub(crate) struct Reader {
+    enabled: bool,
+    accessed: bool,
+    source_date_epoch: Option<Duration>,
+    git: Option<GitTimes>,
+}
+
+impl Reader {
+    pub(crate) fn new(options: &Options) -> Result<Self, Error> {
+        if !options.metadata {
+            return Ok(Reader::default());
+        }
+
+        if !cfg!(feature = "metadata") {
+            return Err(Error::new(
+                options.span,
+                "Embedding metadata requires the \"metadata\" feature",
+            ));
+        }
+
+        let source_date_epoch = match crate::get_env("SOURCE_DATE_EPOCH") {
+            Some(value) if !value.is_empty() => match value.parse() {
+                Ok(secs) => Some(Duration::from_secs(secs)),
+                Err(_) => {
+                    return Err(Error::new(
+                        options.span,
+                        format!(
+                            "SOURCE_DATE_EPOCH should be a number of seconds, found \"{}\"",
+                            value
+                        ),
+                    ))
+                }
+            },
+            _ => None,
+        };
+
+        let git = match options.metadata_modified {
+            ModifiedTime::Filesystem => None,
+            ModifiedTime::Git => Some(GitTimes::load(options)),
+        };
+
+        Ok(Reader {
+            enabled: true,
+            accessed: options.metadata_accessed,
+            source_date_epoch,
+            git,
+        })
+    }
+
+    /// Will any metadata be read?
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Read an entry's metadata, returning `None` if metadata wasn't requested
+    /// or the entry can't be accessed.
+    pub(crate) fn read(&self, path: &Path) -> Option<Metadata> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut metadata = Metadata::from_fs(path)?;
+
+        if !self.accessed {
+            metadata.accessed = None;
+        }
+
+        if let Some(modified) = self.git.as_ref().and_then(|git| git.get(path)) {
+            metadata.modified = Some(modified);
+        }
+
+        if let Some(epoch) = self.source_date_epoch {
+            // user and group IDs depend on the machine doing the build
+            metadata.owner = None;
+
+            let times = [
+                &mut metadata.accessed,
+                &mut metadata.created,
+                &mut metadata.modified,
+            ];
+
+            for time in times.into_iter().flatten() {
+                *time = (*time).min(epoch);
+            }
+        }
+
+        Some(metadata)
+    }
+}
+
+impl Metadata {
+    /// Fields the platform doesn't support (e.g. creation times on many Linux
+    /// filesystems) are left empty instead of discarding everything.
+    fn from_fs(path: &Path) -> Option<Self> {
+        fn since_epoch(time: std::io::Result<SystemTime>) -> Option<Duration> {
+            time.ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()
+        }
+
+        let meta = path.metadata().ok()?;
+
+        Some(Metadata {
+            accessed: since_epoch(meta.accessed()),
+            created: since_epoch(meta.created()),
+            modified: since_epoch(meta.modified()),
+            len: if meta.is_dir() { 0 } else { meta.len() },
+            mode: mode(&meta),
+            owner: owner(&meta),
+        })
+    }
+
+    /// The expression used to construct an `include_dir::Metadata`.
+    pub(crate) fn to_tokens(&self) -> TokenStream {
+        fn duration(d: &Duration) -> TokenStream {
+            let secs = d.as_secs();
+            let nanos = d.subsec_nanos();
+            quote!(std::time::Duration::new(#secs, #nanos))
+        }
+
+        let len = self.len;
+        let mut tokens = quote!(include_dir::Metadata::new().with_len(#len));
+
+        if let Some(accessed) = &self.accessed {
+            let accessed = duration(accessed);
+            tokens = quote!(#tokens.with_accessed(#accessed));
+        }
+        if let Some(created) = &self.created {
+            let created = duration(created);
+            tokens = quote!(#tokens.with_created(#created));
+        }
+        if let Some(modified) = &self.modified {
+            let modified = duration(modified);
+            tokens = quote!(#tokens.with_modified(#modified));
+        }
+        if let Some(mode) = self.mode {
+            tokens = quote!(#tokens.with_mode(#mode));
+        }
+        if let Some((uid, gid)) = self.owner {
+            tokens = quote!(#tokens.with_owner(#uid, #gid));
+        }
+
+        tokens
+    }
+
+    /// Encode the metadata the same way as `include_dir/src/archive.rs`.
+    pub(crate) fn to_archive_bytes(&self) -> [u8; ARCHIVE_LEN] {
+        let mut present = 0;
+        let mut block = [0; ARCHIVE_LEN];
+
+        if let Some(mode) = self.mode {
+            present |= HAS_MODE;
+            block[4..8].copy_from_slice(&mode.to_le_bytes());
+        }
+        if let Some((uid, gid)) = self.owner {
+            present |= HAS_OWNER;
+            block[8..12].copy_from_slice(&uid.to_le_bytes());
+            block[12..16].copy_from_slice(&gid.to_le_bytes());
+        }
+
+        let times = [
+            (HAS_ACCESSED, self.accessed),
+            (HAS_CREATED, self.created),
+            (HAS_MODIFIED, self.modified),
+        ];
+        for (i, (bit, time)) in times.iter().enumerate() {
+            if let Some(time) = time {
+                present |= bit;
+                let nanos = time.as_nanos() as u64;
+                block[16 + i * 8..24 + i * 8].copy_from_slice(&nanos.to_le_bytes());
+            }
+        }
+
+        block[..4].copy_from_slice(&present.to_le_bytes());
+        block
+    }
+}
+
+/// The time of the most recent commit touching each file in the mounted
+/// directories, where a directory's time is the newest time of anything
+/// inside it.
+#[derive(Debug, Default)]
+struct GitTimes {
+    times: HashMap<PathBuf, Duration>,
+}
+
+impl GitTimes {
+    /// Ask git for the history of every mount. Mounts which aren't inside a
+    /// git repository (e.g. a crate downloaded from crates.io) or can't be
+    /// read for some other reason are skipped, so their entries keep the
+    /// filesystem's modified time.
+    fn load(options: &Options) -> Self {
+        let mut git = GitTimes::default();
+
+        for mount in &options.mounts {
+            if let Ok(path) = crate::resolve_path(&mount.path, crate::get_env) {
+                let _ = git.load_path(&path);
+            }
+        }
+
+        git
+    }
+
+    fn load_path(&mut self, path: &Path) -> Option<()> {
+        let dir = if path.is_dir() { path } else { path.parent()? };
+
+        let toplevel = git(dir, &["rev-parse", "--show-toplevel"])?;
+        let toplevel = Path::new(toplevel.trim_end()).canonicalize().ok()?;
+
+        let log = git(
+            dir,
+            &[
+                "-c",
+                "core.quotepath=off",
+                "log",
+                "--format=%x00%ct",
+                "--name-only",
+                "--no-renames",
+                "--",
+                path.to_str()?,
+            ],
+        )?;
+
+        let mut commit_time = None;
+
+        for line in log.lines() {
+            if let Some(secs) = line.strip_prefix('\0') {
+                commit_time = secs.parse().ok().map(Duration::from_secs);
+                continue;
+            }
+
+            let time = match commit_time {
+                Some(time) if !line.is_empty() => time,
+                _ => continue,
+            };
+
+            let mut path = toplevel.join(line);
+
+            while path.starts_with(&toplevel) {
+                let newest = self.times.entry(path.clone()).or_insert(time);
+                *newest = (*newest).max(time);
+
+                if !path.pop() {
+                    break;
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    fn get(&self, path: &Path) -> Option<Duration> {
+        let path = path.canonicalize().ok()?;
+        self.times.get(&path).copied()
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(unix)]
+fn mode(meta: &std::fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    Some(meta.mode())
+}
+
+#[cfg(not(unix))]
+fn mode(_meta: &std::fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+fn owner(meta: &std::fs::Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.uid(), meta.gid()))
+}
+
+#[cfg(not(unix))]
+fn owner(_meta: &std::fs::Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_are_clamped_to_source_date_epoch() {
+        let reader = Reader {
+            enabled: true,
+            accessed: false,
+            source_date_epoch: Some(Duration::from_secs(1)),
+            git: None,
+        };
+
+        let metadata = reader.read(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+
+        assert_eq!(metadata.accessed, None);
+        assert_eq!(metadata.modified, Some(Duration::from_secs(1)));
+        assert_eq!(metadata.owner, None);
+    }
+
+    #[test]
+    fn commit_times_come_from_git() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut git = GitTimes::default();
+
+        if git.load_path(root).is_none() {
+            // not inside a git checkout (e.g. a published crate)
+            return;
+        }
+
+        let cargo_toml = git.get(&root.join("Cargo.toml")).unwrap();
+        let dir = git.get(root).unwrap();
+        assert!(dir >= cargo_toml);
+    }
+
+    #[test]
+    fn missing_fields_are_left_out() {
+        let metadata = Metadata {
+            modified: Some(Duration::new(1_600_000_000, 123)),
+            len: 42,
+            ..Default::default()
+        };
+
+        let tokens = metadata.to_tokens().to_string();
+        assert!(tokens.contains("with_len (42u64)"), "{}", tokens);
+        assert!(tokens.contains("with_modified"), "{}", tokens);
+        assert!(!tokens.contains("with_created"), "{}", tokens);
+        assert!(!tokens.contains("with_owner"), "{}", tokens);
+
+        let bytes = metadata.to_archive_bytes();
+        assert_eq!(&bytes[..4], &HAS_MODIFIED.to_le_bytes());
+        assert_eq!(&bytes[32..], &1_600_000_000_000_000_123_u64.to_le_bytes());
+    }
+}
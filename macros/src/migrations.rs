@@ -0,0 +1,245 @@
+//! Checking that the root of the embedded directory is a valid set of SQL
+//! migrations when `validate_migrations` is enabled.
+//!
+//! This mirrors the discovery rules used by `Dir::migrations()`, and both are
+//! tested against the same table of names in
+//! `include_dir/tests/common/migration_names.rs`.
+
+use crate::{options::Options, tree, Error};
+use std::collections::BTreeMap;
+
+/// Make sure every migration has a well-formed name, an up script and a
+/// unique version, and that no migration has more than one undo script.
+pub(crate) fn validate_migrations(options: &Options, root: &tree::Dir) -> Result<(), Error> {
+    // version -> the path of the migration that provides it
+    let mut versions: BTreeMap<u64, &str> = BTreeMap::new();
+    let mut downs = Vec::new();
+
+    for child in root.children.values() {
+        let (version, sql) = match child {
+            tree::Entry::File(f) if f.path.ends_with(".sql") => {
+                let (kind, version) = parse_file_name(file_name(&f.path))
+                    .ok_or_else(|| invalid_name(options, child))?;
+
+                if kind == 'U' {
+                    downs.push((version, f));
+                    check_utf8(options, f)?;
+                    continue;
+                }
+
+                (version, f)
+            }
+            tree::Entry::Dir(d) if file_name(&d.path).starts_with(|c: char| c.is_ascii_digit()) => {
+                let version = parse_dir_name(file_name(&d.path))
+                    .ok_or_else(|| invalid_name(options, child))?;
+
+                if let Some(tree::Entry::File(down)) = d.children.get("down.sql") {
+                    downs.push((version, down));
+                    check_utf8(options, down)?;
+                }
+
+                match d.children.get("up.sql") {
+                    Some(tree::Entry::File(up)) => (version, up),
+                    _ => {
+                        return Err(Error::new(
+                            options.mounts[d.mount].span,
+                            format!("The migration \"{}\" has no \"up.sql\"", d.path),
+                        ))
+                    }
+                }
+            }
+            _ => continue,
+        };
+
+        check_utf8(options, sql)?;
+
+        if let Some(previous) = versions.insert(version, child.path()) {
+            return Err(Error::new(
+                options.mounts[child.mount()].span,
+                format!(
+                    "\"{}\" and \"{}\" both have version {}",
+                    previous,
+                    child.path(),
+                    version
+                ),
+            ));
+        }
+    }
+
+    // version -> the path of the script that undoes it
+    let mut undone: BTreeMap<u64, &str> = BTreeMap::new();
+
+    for (version, down) in downs {
+        if !versions.contains_key(&version) {
+            return Err(Error::new(
+                options.mounts[down.mount].span,
+                format!("\"{}\" undoes a migration which doesn't exist", down.path),
+            ));
+        }
+
+        if let Some(previous) = undone.insert(version, &down.path) {
+            return Err(Error::new(
+                options.mounts[down.mount].span,
+                format!(
+                    "\"{}\" and \"{}\" both undo version {}",
+                    previous, down.path, version
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid_name(options: &Options, entry: &tree::Entry) -> Error {
+    Error::new(
+        options.mounts[entry.mount()].span,
+        format!(
+            "\"{}\" isn't a valid migration name (expected \"V{{version}}__{{name}}.sql\" or \"{{version}}_{{name}}/up.sql\")",
+            entry.path()
+        ),
+    )
+}
+
+fn check_utf8(options: &Options, file: &tree::File) -> Result<(), Error> {
+    match std::str::from_utf8(&file.read()) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::new(
+            options.mounts[file.mount].span,
+            format!("\"{}\" isn't valid UTF-8: {}", file.path, e),
+        )),
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or_default()
+}
+
+/// Parse `V1__name.sql` or `U1__name.sql` into its kind and version.
+fn parse_file_name(file_name: &str) -> Option<(char, u64)> {
+    let kind = file_name
+        .chars()
+        .next()
+        .filter(|c| *c == 'V' || *c == 'U')?;
+    let (version, name) = file_name[1..].strip_suffix(".sql")?.split_once("__")?;
+
+    if name.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((kind, version.parse().ok()?))
+}
+
+/// Parse `2023-01-31-120000_name` into its version.
+fn parse_dir_name(dir_name: &str) -> Option<u64> {
+    let (version, name) = dir_name.split_once('_')?;
+
+    if name.is_empty() || !version.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+        return None;
+    }
+
+    version.replace('-', "").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn versioned_file_names() {
+        assert_eq!(parse_file_name("V1__create_users.sql"), Some(('V', 1)));
+        assert_eq!(parse_file_name("U12__create_users.sql"), Some(('U', 12)));
+        assert_eq!(parse_file_name("V1_create_users.sql"), None);
+        assert_eq!(parse_file_name("V__create_users.sql"), None);
+        assert_eq!(parse_file_name("V1__.sql"), None);
+        assert_eq!(parse_file_name("create_users.sql"), None);
+    }
+
+    #[test]
+    fn timestamped_dir_names() {
+        assert_eq!(
+            parse_dir_name("2023-01-31-120000_create_users"),
+            Some(20230131120000)
+        );
+        assert_eq!(parse_dir_name("42_answer"), Some(42));
+        assert_eq!(parse_dir_name("42"), None);
+        assert_eq!(parse_dir_name("42-x_answer"), None);
+    }
+
+    mod shared {
+        include!("../../include_dir/tests/common/migration_names.rs");
+    }
+
+    #[test]
+    fn names_are_parsed_like_the_runtime_crate() {
+        for (name, expected) in shared::FILE_NAMES {
+            assert_eq!(parse_file_name(name), *expected, "{}", name);
+        }
+
+        for (name, expected) in shared::DIR_NAMES {
+            assert_eq!(parse_dir_name(name), *expected, "{}", name);
+        }
+    }
+
+    fn file(path: String) -> tree::Entry {
+        tree::Entry::File(tree::File {
+            source: PathBuf::from(&path),
+            path,
+            mount: 0,
+            contents: Some(b"SELECT 1;".to_vec()),
+            text: true,
+        })
+    }
+
+    #[test]
+    fn layouts_are_validated_like_the_runtime_crate() {
+        let options = crate::options::parse(quote::quote!("migrations")).unwrap();
+
+        for (files, dirs, duplicate) in shared::LAYOUTS {
+            let mut children = BTreeMap::new();
+
+            for name in *files {
+                children.insert(name.to_string(), file(name.to_string()));
+            }
+
+            for (name, has_down) in *dirs {
+                let mut scripts = vec!["up.sql"];
+                if *has_down {
+                    scripts.push("down.sql");
+                }
+                let dir = tree::Dir {
+                    path: name.to_string(),
+                    source: PathBuf::from(name),
+                    mount: 0,
+                    children: scripts
+                        .into_iter()
+                        .map(|s| (s.to_string(), file(format!("{}/{}", name, s))))
+                        .collect(),
+                };
+                children.insert(name.to_string(), tree::Entry::Dir(dir));
+            }
+
+            let root = tree::Dir {
+                path: String::new(),
+                source: PathBuf::new(),
+                mount: 0,
+                children,
+            };
+            let result = validate_migrations(&options, &root);
+
+            match duplicate {
+                Some(version) => {
+                    let message = result.unwrap_err().message;
+                    assert!(
+                        message.ends_with(&format!("version {}", version)),
+                        "{:?}: {}",
+                        files,
+                        message
+                    );
+                }
+                None => assert!(result.is_ok(), "{:?}", files),
+            }
+        }
+    }
+}
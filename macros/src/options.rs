@@ -0,0 +1,804 @@
+//! Parsing the arguments passed to `include_dir!()`.
+
+use crate::Error;
+use proc_macro2::{Delimiter, Ident, Literal, Span, TokenStream, TokenTree};
+use std::iter::Peekable;
+
+const USAGE: &str =
+    "This macro expects a string literal or a `{ \"mount point\" => \"path\" }` map, optionally followed by a list of `key = value` options";
+
+/// The arguments passed to `include_dir!()`.
+#[derive(Debug, Clone)]
+pub(crate) struct Options {
+    /// The directories being included, in the order they were declared.
+    pub(crate) mounts: Vec<Mount>,
+    /// The location of the path argument, used when reporting errors.
+    pub(crate) span: Span,
+    /// Fail if two entries' paths only differ by case.
+    pub(crate) deny_case_collisions: bool,
+    /// What to do with paths that aren't valid UTF-8.
+    pub(crate) non_utf8_paths: NonUtf8Paths,
+    /// Convert every embedded path to Unicode Normalization Form C.
+    pub(crate) normalize_unicode: bool,
+    /// What to do when two mounts provide the same path.
+    pub(crate) on_conflict: OnConflict,
+    /// Fail if a data file (JSON, TOML, etc.) can't be parsed.
+    pub(crate) validate_syntax: bool,
+    /// Fail if the directory isn't a valid set of SQL migrations.
+    pub(crate) validate_migrations: bool,
+    /// Fail if the directory's locales don't all have the same files.
+    pub(crate) validate_locales: bool,
+    /// Embed each entry's filesystem metadata.
+    pub(crate) metadata: bool,
+    /// Include the time each entry was last accessed in its metadata.
+    pub(crate) metadata_accessed: bool,
+    /// Where each entry's modified time comes from.
+    pub(crate) metadata_modified: ModifiedTime,
+    /// Fail if any file is larger than this many bytes.
+    pub(crate) max_file_size: Option<Limit>,
+    /// Fail if the files add up to more than this many bytes.
+    pub(crate) max_total_size: Option<Limit>,
+    /// Fail if more than this many files are embedded.
+    pub(crate) max_files: Option<Limit>,
+    /// Emit a warning summarising how much is being embedded.
+    pub(crate) size_report: bool,
+    /// Give each file a second path containing a hash of its contents.
+    pub(crate) fingerprint: bool,
+    /// Files to remove a leading UTF-8 byte order mark from.
+    pub(crate) strip_bom: FileFilter,
+    /// Files to convert `\r\n` line endings to `\n` in.
+    pub(crate) normalize_line_endings: FileFilter,
+    /// Files to remove whitespace from the end of each line in.
+    pub(crate) trim_trailing_whitespace: FileFilter,
+    /// Files which must be valid UTF-8, and are embedded as text.
+    pub(crate) text: FileFilter,
+    /// How each file's contents should be aligned in memory.
+    pub(crate) align: Alignment,
+}
+
+/// The alignment of each file's contents, set using a number for every file
+/// or a `{ "glob" => number }` map.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Alignment {
+    rules: Vec<(Option<glob::Pattern>, u64)>,
+}
+
+impl Alignment {
+    /// The alignment for a file with this path (relative to the embedded
+    /// root), if it needs one. When several rules match, the largest
+    /// alignment wins.
+    pub(crate) fn for_file(&self, path: &str) -> Option<u64> {
+        self.rules
+            .iter()
+            .filter(|(pattern, _)| pattern.as_ref().map_or(true, |p| p.matches(path)))
+            .map(|(_, align)| *align)
+            .max()
+    }
+}
+
+/// The files an option applies to, set using `true`, a glob pattern or a
+/// list of glob patterns.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileFilter {
+    all: bool,
+    patterns: Vec<glob::Pattern>,
+}
+
+impl FileFilter {
+    /// Does this apply to the file with this path (relative to the embedded
+    /// root)?
+    pub(crate) fn matches(&self, path: &str) -> bool {
+        self.all || self.patterns.iter().any(|p| p.matches(path))
+    }
+}
+
+/// An upper bound set by one of the `max_*` options.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Limit {
+    pub(crate) max: u64,
+    /// The location of the option's value, used when reporting errors.
+    pub(crate) span: Span,
+}
+
+/// A directory to be included at a particular location in the embedded tree.
+#[derive(Debug, Clone)]
+pub(crate) struct Mount {
+    /// Where the directory's contents will be placed, relative to the
+    /// embedded root (`""` is the root itself).
+    pub(crate) point: String,
+    /// The (unresolved) path to the directory being included.
+    pub(crate) path: String,
+    /// The location of the path, used when reporting errors.
+    pub(crate) span: Span,
+}
+
+/// What to do when two mounts provide the same path.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum OnConflict {
+    /// Report a compile error.
+    Error,
+    /// Keep the entry from the mount declared first.
+    First,
+    /// Keep the entry from the mount declared last.
+    Last,
+}
+
+/// Where the modified time in an entry's metadata comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ModifiedTime {
+    /// The filesystem's modified time.
+    Filesystem,
+    /// The time of the last git commit that touched the entry.
+    Git,
+}
+
+/// How to handle paths that aren't valid UTF-8.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum NonUtf8Paths {
+    /// Report a compile error.
+    Error,
+    /// Replace invalid sequences with `U+FFFD REPLACEMENT CHARACTER`.
+    Lossy,
+}
+
+impl Options {
+    fn new(mounts: Vec<Mount>, span: Span) -> Self {
+        Options {
+            mounts,
+            span,
+            deny_case_collisions: false,
+            non_utf8_paths: NonUtf8Paths::Error,
+            normalize_unicode: false,
+            on_conflict: OnConflict::Error,
+            validate_syntax: false,
+            validate_migrations: false,
+            validate_locales: false,
+            metadata: false,
+            metadata_accessed: true,
+            metadata_modified: ModifiedTime::Filesystem,
+            max_file_size: None,
+            max_total_size: None,
+            max_files: None,
+            size_report: false,
+            fingerprint: false,
+            strip_bom: FileFilter::default(),
+            normalize_line_endings: FileFilter::default(),
+            trim_trailing_whitespace: FileFilter::default(),
+            text: FileFilter::default(),
+            align: Alignment::default(),
+        }
+    }
+
+    fn set(&mut self, key: &Ident, value: Value) -> Result<(), Error> {
+        match key.to_string().as_str() {
+            "deny_case_collisions" => self.deny_case_collisions = value.into_bool()?,
+            "normalize_unicode" => {
+                let span = value.span();
+                self.normalize_unicode = value.into_bool()?;
+
+                if self.normalize_unicode && !cfg!(feature = "nfc") {
+                    return Err(Error::new(
+                        span,
+                        "Normalizing paths requires the \"nfc\" feature",
+                    ));
+                }
+            }
+            "validate_syntax" => self.validate_syntax = value.into_bool()?,
+            "validate_migrations" => self.validate_migrations = value.into_bool()?,
+            "validate_locales" => {
+                let span = value.span();
+                self.validate_locales = value.into_bool()?;
+
+                if self.validate_locales && !cfg!(feature = "fluent") {
+                    return Err(Error::new(
+                        span,
+                        "Validating locales requires the \"fluent\" feature",
+                    ));
+                }
+            }
+            "metadata" => self.metadata = value.into_bool()?,
+            "metadata_accessed" => self.metadata_accessed = value.into_bool()?,
+            "max_file_size" => self.max_file_size = Some(value.into_size()?),
+            "max_total_size" => self.max_total_size = Some(value.into_size()?),
+            "max_files" => self.max_files = Some(value.into_int()?),
+            "size_report" => self.size_report = value.into_bool()?,
+            "fingerprint" => self.fingerprint = value.into_bool()?,
+            "strip_bom" => self.strip_bom = value.into_filter()?,
+            "normalize_line_endings" => self.normalize_line_endings = value.into_filter()?,
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = value.into_filter()?,
+            "text" => self.text = value.into_filter()?,
+            "align" => self.align = value.into_alignment()?,
+            "metadata_modified" => {
+                self.metadata_modified = match value.into_str()? {
+                    (s, _) if s == "filesystem" => ModifiedTime::Filesystem,
+                    (s, _) if s == "git" => ModifiedTime::Git,
+                    (_, span) => {
+                        return Err(Error::new(span, "Expected \"filesystem\" or \"git\""))
+                    }
+                }
+            }
+            "non_utf8_paths" => {
+                self.non_utf8_paths = match value.into_str()? {
+                    (s, _) if s == "error" => NonUtf8Paths::Error,
+                    (s, _) if s == "lossy" => NonUtf8Paths::Lossy,
+                    (_, span) => return Err(Error::new(span, "Expected \"error\" or \"lossy\"")),
+                }
+            }
+            "on_conflict" => {
+                self.on_conflict = match value.into_str()? {
+                    (s, _) if s == "error" => OnConflict::Error,
+                    (s, _) if s == "first" => OnConflict::First,
+                    (s, _) if s == "last" => OnConflict::Last,
+                    (_, span) => {
+                        return Err(Error::new(
+                            span,
+                            "Expected \"error\", \"first\" or \"last\"",
+                        ))
+                    }
+                }
+            }
+            other => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("Unknown option, \"{}\"", other),
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse the tokens passed to `include_dir!()`.
+pub(crate) fn parse(input: TokenStream) -> Result<Options, Error> {
+    let mut tokens = input.into_iter().peekable();
+
+    let mut options = match tokens.next() {
+        Some(TokenTree::Literal(lit)) => {
+            let mount = Mount {
+                point: String::new(),
+                path: unwrap_string_literal(&lit)?,
+                span: lit.span(),
+            };
+            Options::new(vec![mount], lit.span())
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            let mounts = parse_mounts(group.stream())?;
+            if mounts.is_empty() {
+                return Err(Error::new(group.span(), "Expected at least one mount"));
+            }
+            Options::new(mounts, group.span())
+        }
+        Some(other) => return Err(Error::new(other.span(), USAGE)),
+        None => return Err(Error::new(Span::call_site(), USAGE)),
+    };
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == ',' => {}
+            other => return Err(Error::new(other.span(), USAGE)),
+        }
+
+        let key = match tokens.next() {
+            Some(TokenTree::Ident(key)) => key,
+            // trailing comma
+            None => break,
+            Some(other) => return Err(Error::new(other.span(), USAGE)),
+        };
+
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == '=' => {}
+            _ => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("Expected a value for the \"{}\" option", key),
+                ))
+            }
+        }
+
+        let value = Value::parse(&key, &mut tokens)?;
+        options.set(&key, value)?;
+    }
+
+    Ok(options)
+}
+
+/// Parse the `"mount point" => "path"` pairs inside a `{ ... }` block.
+fn parse_mounts(input: TokenStream) -> Result<Vec<Mount>, Error> {
+    const MOUNT_USAGE: &str = "Expected `\"mount point\" => \"path\"`";
+
+    let mut tokens = input.into_iter();
+    let mut mounts = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let point = match token {
+            TokenTree::Literal(lit) => unwrap_string_literal(&lit)?,
+            other => return Err(Error::new(other.span(), MOUNT_USAGE)),
+        };
+
+        for expected in ['=', '>'] {
+            match tokens.next() {
+                Some(TokenTree::Punct(p)) if p.as_char() == expected => {}
+                Some(other) => return Err(Error::new(other.span(), MOUNT_USAGE)),
+                None => return Err(Error::new(Span::call_site(), MOUNT_USAGE)),
+            }
+        }
+
+        let (path, span) = match tokens.next() {
+            Some(TokenTree::Literal(lit)) => (unwrap_string_literal(&lit)?, lit.span()),
+            Some(other) => return Err(Error::new(other.span(), MOUNT_USAGE)),
+            None => return Err(Error::new(Span::call_site(), MOUNT_USAGE)),
+        };
+
+        mounts.push(Mount { point, path, span });
+
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+            Some(other) => return Err(Error::new(other.span(), MOUNT_USAGE)),
+            None => break,
+        }
+    }
+
+    Ok(mounts)
+}
+
+pub(crate) fn unwrap_string_literal(lit: &Literal) -> Result<String, Error> {
+    let mut repr = lit.to_string();
+    if repr.len() < 2 || !repr.starts_with('"') || !repr.ends_with('"') {
+        return Err(Error::new(lit.span(), "Expected a string literal"));
+    }
+
+    repr.remove(0);
+    repr.pop();
+
+    Ok(repr)
+}
+
+/// The value half of a `key = value` option.
+#[derive(Debug, Clone)]
+enum Value {
+    Bool(bool, Span),
+    Str(String, Span),
+    Int(u64, Span),
+    List(Vec<(String, Span)>, Span),
+    Map(Vec<(String, Span, Value)>, Span),
+}
+
+impl Value {
+    fn parse(
+        key: &Ident,
+        tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+    ) -> Result<Value, Error> {
+        match tokens.next() {
+            Some(TokenTree::Ident(ident)) if ident == "true" => Ok(Value::Bool(true, ident.span())),
+            Some(TokenTree::Ident(ident)) if ident == "false" => {
+                Ok(Value::Bool(false, ident.span()))
+            }
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                parse_list(group.stream()).map(|items| Value::List(items, group.span()))
+            }
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                parse_map(key, group.stream()).map(|items| Value::Map(items, group.span()))
+            }
+            Some(TokenTree::Literal(lit)) => match unwrap_integer_literal(&lit) {
+                Some(n) => Ok(Value::Int(n, lit.span())),
+                None => unwrap_string_literal(&lit).map(|s| Value::Str(s, lit.span())),
+            },
+            Some(other) => Err(Error::new(other.span(), "Unsupported option value")),
+            None => Err(Error::new(
+                key.span(),
+                format!("Expected a value for the \"{}\" option", key),
+            )),
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Value::Bool(_, span)
+            | Value::Str(_, span)
+            | Value::Int(_, span)
+            | Value::List(_, span)
+            | Value::Map(_, span) => *span,
+        }
+    }
+
+    fn into_bool(self) -> Result<bool, Error> {
+        match self {
+            Value::Bool(b, _) => Ok(b),
+            other => Err(Error::new(other.span(), "Expected `true` or `false`")),
+        }
+    }
+
+    fn into_str(self) -> Result<(String, Span), Error> {
+        match self {
+            Value::Str(s, span) => Ok((s, span)),
+            other => Err(Error::new(other.span(), "Expected a string")),
+        }
+    }
+
+    fn into_int(self) -> Result<Limit, Error> {
+        match self {
+            Value::Int(max, span) => Ok(Limit { max, span }),
+            other => Err(Error::new(other.span(), "Expected an integer")),
+        }
+    }
+
+    /// `true` for every file, `false` for none, or one or more glob patterns.
+    fn into_filter(self) -> Result<FileFilter, Error> {
+        let globs = match self {
+            Value::Bool(all, _) => {
+                return Ok(FileFilter {
+                    all,
+                    patterns: Vec::new(),
+                })
+            }
+            Value::Str(s, span) => vec![(s, span)],
+            Value::List(items, _) => items,
+            other => {
+                return Err(Error::new(
+                    other.span(),
+                    "Expected `true`, `false`, a glob pattern or a list of glob patterns",
+                ))
+            }
+        };
+
+        let patterns = globs
+            .into_iter()
+            .map(|(glob, span)| {
+                glob::Pattern::new(&glob)
+                    .map_err(|e| Error::new(span, format!("Invalid glob pattern: {}", e)))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(FileFilter {
+            all: false,
+            patterns,
+        })
+    }
+
+    /// A power of two for every file, or a map from glob patterns to powers
+    /// of two.
+    fn into_alignment(self) -> Result<Alignment, Error> {
+        fn power_of_two(value: Value) -> Result<u64, Error> {
+            let span = value.span();
+            let Limit { max: align, .. } = value.into_int()?;
+
+            // the largest alignment `#[repr(align)]` accepts
+            if align.is_power_of_two() && align <= 1 << 29 {
+                Ok(align)
+            } else {
+                Err(Error::new(
+                    span,
+                    "The alignment must be a power of two, no larger than 2^29",
+                ))
+            }
+        }
+
+        let rules = match self {
+            Value::Map(entries, _) => entries
+                .into_iter()
+                .map(|(glob, span, value)| {
+                    let pattern = glob::Pattern::new(&glob)
+                        .map_err(|e| Error::new(span, format!("Invalid glob pattern: {}", e)))?;
+                    Ok((Some(pattern), power_of_two(value)?))
+                })
+                .collect::<Result<_, Error>>()?,
+            other => vec![(None, power_of_two(other)?)],
+        };
+
+        Ok(Alignment { rules })
+    }
+
+    /// A number of bytes, either as an integer or a string with a unit like
+    /// `"10 MiB"`.
+    fn into_size(self) -> Result<Limit, Error> {
+        match self {
+            Value::Str(s, span) => match parse_size(&s) {
+                Some(max) => Ok(Limit { max, span }),
+                None => Err(Error::new(
+                    span,
+                    "Expected a size like \"512 KiB\", \"10 MB\" or \"1 GiB\"",
+                )),
+            },
+            other => other.into_int(),
+        }
+    }
+}
+
+/// Parse the string literals inside a `[...]` list.
+fn parse_list(input: TokenStream) -> Result<Vec<(String, Span)>, Error> {
+    let mut tokens = input.into_iter();
+    let mut items = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Literal(lit) => items.push((unwrap_string_literal(&lit)?, lit.span())),
+            other => return Err(Error::new(other.span(), "Expected a string literal")),
+        }
+
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+            Some(other) => return Err(Error::new(other.span(), "Expected a comma")),
+            None => break,
+        }
+    }
+
+    Ok(items)
+}
+
+/// Parse the `"key" => value` pairs inside a `{ ... }` map.
+fn parse_map(option: &Ident, input: TokenStream) -> Result<Vec<(String, Span, Value)>, Error> {
+    const MAP_USAGE: &str = "Expected `\"pattern\" => value`";
+
+    let mut tokens = input.into_iter().peekable();
+    let mut entries = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        let (key, span) = match token {
+            TokenTree::Literal(lit) => (unwrap_string_literal(&lit)?, lit.span()),
+            other => return Err(Error::new(other.span(), MAP_USAGE)),
+        };
+
+        for expected in ['=', '>'] {
+            match tokens.next() {
+                Some(TokenTree::Punct(p)) if p.as_char() == expected => {}
+                Some(other) => return Err(Error::new(other.span(), MAP_USAGE)),
+                None => return Err(Error::new(span, MAP_USAGE)),
+            }
+        }
+
+        entries.push((key, span, Value::parse(option, &mut tokens)?));
+
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+            Some(other) => return Err(Error::new(other.span(), MAP_USAGE)),
+            None => break,
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Parse an unsigned integer literal like `1_000` or `42u64`.
+fn unwrap_integer_literal(lit: &Literal) -> Option<u64> {
+    let repr = lit.to_string();
+    if !repr.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let digits: String = repr
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '_')
+        .filter(|c| *c != '_')
+        .collect();
+    let suffix = &repr[repr
+        .find(|c: char| !c.is_ascii_digit() && c != '_')
+        .unwrap_or(repr.len())..];
+
+    match suffix {
+        "" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+        | "i128" | "isize" => digits.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse a human-readable size like `"10 MB"` or `"1.5GiB"` into bytes.
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+
+    let multiplier: u64 = match unit.trim() {
+        "" | "B" => 1,
+        "KB" | "kB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    };
+
+    match number.parse::<u64>() {
+        Ok(n) => n.checked_mul(multiplier),
+        Err(_) => {
+            let n: f64 = number.parse().ok()?;
+            Some((n * multiplier as f64) as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn just_a_path() {
+        let options = parse(quote!("$CARGO_MANIFEST_DIR/assets")).unwrap();
+
+        assert_eq!(options.mounts.len(), 1);
+        assert_eq!(options.mounts[0].point, "");
+        assert_eq!(options.mounts[0].path, "$CARGO_MANIFEST_DIR/assets");
+        assert!(!options.deny_case_collisions);
+    }
+
+    #[test]
+    fn path_with_options() {
+        let options = parse(quote!("assets", deny_case_collisions = true,)).unwrap();
+
+        assert_eq!(options.mounts[0].path, "assets");
+        assert!(options.deny_case_collisions);
+    }
+
+    #[test]
+    fn multiple_mounts() {
+        let options = parse(quote!(
+            {
+                "" => "$CARGO_MANIFEST_DIR/static",
+                "vendor" => "$OUT_DIR/vendor",
+            },
+            on_conflict = "last",
+        ))
+        .unwrap();
+
+        let mounts: Vec<_> = options
+            .mounts
+            .iter()
+            .map(|m| (m.point.as_str(), m.path.as_str()))
+            .collect();
+        assert_eq!(
+            mounts,
+            [
+                ("", "$CARGO_MANIFEST_DIR/static"),
+                ("vendor", "$OUT_DIR/vendor")
+            ]
+        );
+        assert_eq!(options.on_conflict, OnConflict::Last);
+    }
+
+    #[test]
+    fn malformed_mounts() {
+        assert!(parse(quote!({})).is_err());
+        assert!(parse(quote!({ "" "assets" })).is_err());
+        assert!(parse(quote!({ "" => assets })).is_err());
+        assert!(parse(quote!({ "" => "assets" "vendor" => "vendor" })).is_err());
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        let err = parse(quote!("assets", whatever = true)).unwrap_err();
+
+        assert_eq!(err.message, "Unknown option, \"whatever\"");
+    }
+
+    #[test]
+    fn reproducible_metadata() {
+        let options = parse(quote!("assets")).unwrap();
+        assert!(!options.metadata);
+        assert!(options.metadata_accessed);
+        assert_eq!(options.metadata_modified, ModifiedTime::Filesystem);
+
+        let options = parse(quote!(
+            "assets",
+            metadata = true,
+            metadata_accessed = false,
+            metadata_modified = "git",
+        ))
+        .unwrap();
+        assert!(options.metadata);
+        assert!(!options.metadata_accessed);
+        assert_eq!(options.metadata_modified, ModifiedTime::Git);
+
+        let err = parse(quote!("assets", metadata_modified = "mtime")).unwrap_err();
+        assert_eq!(err.message, "Expected \"filesystem\" or \"git\"");
+    }
+
+    #[test]
+    fn size_limits() {
+        let options = parse(quote!(
+            "assets",
+            max_file_size = "1.5 MiB",
+            max_total_size = 10_000_000,
+            max_files = 200,
+            size_report = true,
+        ))
+        .unwrap();
+
+        assert_eq!(options.max_file_size.unwrap().max, 1_572_864);
+        assert_eq!(options.max_total_size.unwrap().max, 10_000_000);
+        assert_eq!(options.max_files.unwrap().max, 200);
+        assert!(options.size_report);
+
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("10 MB"), Some(10_000_000));
+        assert_eq!(parse_size("1GiB"), Some(1 << 30));
+        assert_eq!(parse_size("10 parsecs"), None);
+
+        let err = parse(quote!("assets", max_files = "lots")).unwrap_err();
+        assert_eq!(err.message, "Expected an integer");
+        assert!(parse(quote!("assets", max_file_size = "big")).is_err());
+        assert!(parse(quote!("assets", max_files = 1.5)).is_err());
+    }
+
+    #[test]
+    fn file_filters() {
+        let options = parse(quote!(
+            "assets",
+            strip_bom = true,
+            normalize_line_endings = "**/*.txt",
+            trim_trailing_whitespace = ["*.md", "templates/**",],
+        ))
+        .unwrap();
+
+        assert!(options.strip_bom.matches("image.png"));
+        assert!(options.normalize_line_endings.matches("a/b.txt"));
+        assert!(!options.normalize_line_endings.matches("a/b.html"));
+        assert!(options.trim_trailing_whitespace.matches("README.md"));
+        assert!(options
+            .trim_trailing_whitespace
+            .matches("templates/index.html"));
+        assert!(!options.text.matches("README.md"));
+
+        let err = parse(quote!("assets", text = "[")).unwrap_err();
+        assert!(err.message.starts_with("Invalid glob pattern"));
+        assert!(parse(quote!("assets", text = [true])).is_err());
+        assert!(parse(quote!("assets", text = 42)).is_err());
+    }
+
+    #[test]
+    fn locale_validation_needs_the_fluent_feature() {
+        let result = parse(quote!("assets", validate_locales = true));
+
+        assert_eq!(result.is_ok(), cfg!(feature = "fluent"));
+        if let Err(e) = result {
+            assert!(e.message.contains("\"fluent\" feature"));
+        }
+    }
+
+    #[test]
+    fn unicode_normalization_needs_the_nfc_feature() {
+        let result = parse(quote!("assets", normalize_unicode = true));
+
+        assert_eq!(result.is_ok(), cfg!(feature = "nfc"));
+        if let Err(e) = result {
+            assert!(e.message.contains("\"nfc\" feature"));
+        }
+        assert!(!parse(quote!("assets")).unwrap().normalize_unicode);
+    }
+
+    #[test]
+    fn alignment() {
+        let options = parse(quote!("assets", align = 16)).unwrap();
+        assert_eq!(options.align.for_file("mesh.bin"), Some(16));
+
+        let options = parse(quote!(
+            "assets",
+            align = { "**/*.bin" => 16, "lut/*" => 64 },
+        ))
+        .unwrap();
+        assert_eq!(options.align.for_file("meshes/cube.bin"), Some(16));
+        assert_eq!(options.align.for_file("lut/gamma.bin"), Some(64));
+        assert_eq!(options.align.for_file("README.md"), None);
+
+        let err = parse(quote!("assets", align = 12)).unwrap_err();
+        assert!(err
+            .message
+            .starts_with("The alignment must be a power of two"));
+        assert!(parse(quote!("assets", align = { "*.bin" => "16" })).is_err());
+        assert!(parse(quote!("assets", align = { "*.bin" 16 })).is_err());
+        assert_eq!(
+            parse(quote!("assets")).unwrap().align.for_file("a.bin"),
+            None
+        );
+    }
+
+    #[test]
+    fn the_path_must_be_a_string() {
+        assert!(parse(quote!(assets)).is_err());
+        assert!(parse(quote!()).is_err());
+        assert!(parse(quote!("assets", deny_case_collisions = "yes")).is_err());
+    }
+}
@@ -0,0 +1,351 @@
+//! Normalizing text files as they are embedded using the `strip_bom`,
+//! `normalize_line_endings`, `trim_trailing_whitespace` and `text` options,
+//! and detecting which files are text. Files which aren't valid UTF-8 are
+//! never transformed.
+
+use crate::{options::Options, tree, Error};
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// How much of a file to read at a time when checking if it is UTF-8.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Apply every transform which matches each file in the tree, and mark every
+/// file which is valid UTF-8 as text.
+pub(crate) fn transform(options: &Options, dir: &mut tree::Dir) -> Result<(), Error> {
+    for child in dir.children.values_mut() {
+        match child {
+            tree::Entry::Dir(d) => transform(options, d)?,
+            tree::Entry::File(f) => transform_file(options, f)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn transform_file(options: &Options, file: &mut tree::File) -> Result<(), Error> {
+    let path = file.path.as_str();
+    let strip_bom = options.strip_bom.matches(path);
+    let normalize_line_endings = options.normalize_line_endings.matches(path);
+    let trim_trailing_whitespace = options.trim_trailing_whitespace.matches(path);
+    let text = options.text.matches(path);
+
+    if !(strip_bom || normalize_line_endings || trim_trailing_whitespace) {
+        let invalid = match &file.contents {
+            Some(contents) => invalid_utf8(contents),
+            None => invalid_utf8_in_file(&file.source).map_err(|e| {
+                Error::new(
+                    options.mounts[file.mount].span,
+                    format!("Unable to read \"{}\": {}", file.source.display(), e),
+                )
+            })?,
+        };
+        file.text = is_text(options, file, invalid, text)?;
+        return Ok(());
+    }
+
+    let original = file.read().into_owned();
+    let invalid = invalid_utf8(&original);
+
+    if invalid.is_some() {
+        // rewriting bytes which happen to look like line endings or
+        // whitespace would corrupt binary files (e.g. a PNG's signature)
+        file.text = is_text(options, file, invalid, text)?;
+        return Ok(());
+    }
+
+    let mut contents = original.clone();
+
+    if strip_bom && contents.starts_with(BOM) {
+        contents.drain(..BOM.len());
+    }
+    if normalize_line_endings {
+        contents = replace_crlf(&contents);
+    }
+    if trim_trailing_whitespace {
+        contents = trim_lines(&contents);
+    }
+
+    file.text = is_text(options, file, invalid_utf8(&contents), text)?;
+
+    if contents != original {
+        file.contents = Some(contents);
+    }
+
+    Ok(())
+}
+
+/// Files are text if they are valid UTF-8, and it is an error for a file
+/// matching the `text` option not to be.
+fn is_text(
+    options: &Options,
+    file: &tree::File,
+    invalid: Option<usize>,
+    required: bool,
+) -> Result<bool, Error> {
+    match invalid {
+        None => Ok(true),
+        Some(offset) if required => Err(Error::new(
+            options.mounts[file.mount].span,
+            format!(
+                "\"{}\" should be text, but byte {} isn't valid UTF-8",
+                file.path, offset
+            ),
+        )),
+        Some(_) => Ok(false),
+    }
+}
+
+/// The offset of the first byte which isn't part of a valid UTF-8 sequence,
+/// if there is one.
+fn invalid_utf8(contents: &[u8]) -> Option<usize> {
+    std::str::from_utf8(contents).err().map(|e| e.valid_up_to())
+}
+
+/// Like [`invalid_utf8()`], but reading the file a chunk at a time and
+/// stopping at the first invalid byte, so checking a large binary file
+/// doesn't mean loading all of it into memory.
+fn invalid_utf8_in_file(path: &Path) -> io::Result<Option<usize>> {
+    crate::track_path(path);
+    invalid_utf8_in_reader(fs::File::open(path)?, CHUNK_SIZE)
+}
+
+fn invalid_utf8_in_reader(mut reader: impl Read, chunk_size: usize) -> io::Result<Option<usize>> {
+    // room for a whole chunk after a cut off character
+    let mut buffer = vec![0; chunk_size + 3];
+    // the offset of buffer[0] in the file
+    let mut offset = 0;
+    // bytes at the start of the buffer which are part of a character that
+    // was cut off at the end of the previous chunk
+    let mut pending = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer[pending..pending + chunk_size]) {
+            Ok(0) if pending > 0 => return Ok(Some(offset)),
+            Ok(0) => return Ok(None),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let filled = pending + read;
+
+        match std::str::from_utf8(&buffer[..filled]) {
+            Ok(_) => {
+                offset += filled;
+                pending = 0;
+            }
+            Err(e) if e.error_len().is_none() => {
+                let valid = e.valid_up_to();
+                buffer.copy_within(valid..filled, 0);
+                offset += valid;
+                pending = filled - valid;
+            }
+            Err(e) => return Ok(Some(offset + e.valid_up_to())),
+        }
+    }
+}
+
+fn replace_crlf(contents: &[u8]) -> Vec<u8> {
+    let mut normalized = Vec::with_capacity(contents.len());
+    let mut bytes = contents.iter().peekable();
+
+    while let Some(&b) = bytes.next() {
+        if b == b'\r' && bytes.peek() == Some(&&b'\n') {
+            continue;
+        }
+        normalized.push(b);
+    }
+
+    normalized
+}
+
+/// Remove spaces and tabs from the end of each line, leaving any `\r\n` line
+/// endings intact.
+fn trim_lines(contents: &[u8]) -> Vec<u8> {
+    let mut trimmed = Vec::with_capacity(contents.len());
+
+    for (i, line) in contents.split(|b| *b == b'\n').enumerate() {
+        if i > 0 {
+            trimmed.push(b'\n');
+        }
+
+        let (line, cr) = match line.strip_suffix(b"\r") {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let end = line
+            .iter()
+            .rposition(|b| *b != b' ' && *b != b'\t')
+            .map_or(0, |ix| ix + 1);
+
+        trimmed.extend_from_slice(&line[..end]);
+        if cr {
+            trimmed.push(b'\r');
+        }
+    }
+
+    trimmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../include_dir/tests/fixtures")
+            .join(path)
+    }
+
+    fn transform_source(
+        options: proc_macro2::TokenStream,
+        source: PathBuf,
+        contents: Option<&[u8]>,
+    ) -> Result<tree::File, Error> {
+        let options = crate::options::parse(options).unwrap();
+        let mut file = tree::File {
+            path: String::from("file.txt"),
+            source,
+            mount: 0,
+            contents: contents.map(<[u8]>::to_vec),
+            text: false,
+        };
+
+        transform_file(&options, &mut file).map(|_| file)
+    }
+
+    /// Transform a file which only exists in memory.
+    fn transformed(
+        options: proc_macro2::TokenStream,
+        contents: &[u8],
+    ) -> Result<tree::File, Error> {
+        transform_source(options, PathBuf::from("file.txt"), Some(contents))
+    }
+
+    #[test]
+    fn normalize_a_windows_file() {
+        let file = transformed(
+            quote::quote!(
+                "assets",
+                strip_bom = true,
+                normalize_line_endings = "*.txt",
+                trim_trailing_whitespace = true,
+                text = true,
+            ),
+            b"\xEF\xBB\xBFHello,  \r\nWorld!\t\r\n\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(file.contents.as_deref(), Some(&b"Hello,\nWorld!\n\n"[..]));
+        assert!(file.text);
+    }
+
+    #[test]
+    fn only_matching_files_are_transformed() {
+        let file = transformed(
+            quote::quote!("assets", normalize_line_endings = "*.md"),
+            b"Hello\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(file.contents.as_deref(), Some(&b"Hello\r\n"[..]));
+        assert!(file.text);
+    }
+
+    #[test]
+    fn binary_files_arent_text() {
+        let file = transformed(quote::quote!("assets"), b"\x89PNG\xff").unwrap();
+
+        assert!(!file.text);
+    }
+
+    #[test]
+    fn binary_files_are_never_transformed() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR \t\r\n";
+
+        let file = transformed(
+            quote::quote!(
+                "assets",
+                strip_bom = true,
+                normalize_line_endings = true,
+                trim_trailing_whitespace = true,
+            ),
+            png,
+        )
+        .unwrap();
+
+        assert_eq!(file.contents.as_deref(), Some(&png[..]));
+        assert!(!file.text);
+    }
+
+    #[test]
+    fn unchanged_files_are_read_from_disk() {
+        let source = fixture("data/server.json");
+
+        let file = transform_source(
+            quote::quote!("assets", strip_bom = true),
+            source.clone(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(file.contents, None);
+        assert_eq!(file.read(), std::fs::read(source).unwrap());
+    }
+
+    #[test]
+    fn trailing_whitespace_keeps_crlf() {
+        assert_eq!(trim_lines(b"a  \r\nb\t\n c "), b"a\r\nb\n c");
+        assert_eq!(replace_crlf(b"a\r\nb\rc\r\n"), b"a\nb\rc\n");
+    }
+
+    #[test]
+    fn utf8_is_checked_a_chunk_at_a_time() {
+        let inputs: &[&[u8]] = &[
+            b"",
+            b"plain ASCII",
+            "h\u{e9}llo w\u{f6}rld \u{1f980}".as_bytes(),
+            b"ab\xffcd",
+            b"\x89PNG\r\n\x1a\n",
+            b"cut off \xf0\x9f\xa6",
+        ];
+
+        for input in inputs {
+            for chunk_size in 1..8 {
+                assert_eq!(
+                    invalid_utf8_in_reader(*input, chunk_size).unwrap(),
+                    invalid_utf8(input),
+                    "{:?} in chunks of {}",
+                    input,
+                    chunk_size
+                );
+            }
+        }
+
+        assert_eq!(
+            invalid_utf8_in_file(&fixture("text/binary.bin")).unwrap(),
+            Some(0)
+        );
+        assert_eq!(
+            invalid_utf8_in_file(&fixture("text/windows.ini")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn text_must_be_utf8() {
+        let err = transformed(quote::quote!("assets", text = "*.txt"), b"\xff\xfe").unwrap_err();
+
+        assert!(
+            err.message.starts_with("\"file.txt\" should be text, but "),
+            "{}",
+            err.message
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! An in-memory copy of the directory tree that will be embedded.
+
+use crate::{
+    archive::{fnv1a, Fnv1a},
+    options::OnConflict,
+};
+use std::{borrow::Cow, collections::BTreeMap, fs, io, path::PathBuf};
+
+/// A directory that will be embedded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Dir {
+    /// The path relative to the embedded root, using `/` as a separator.
+    pub(crate) path: String,
+    /// Where the directory came from on disk.
+    pub(crate) source: PathBuf,
+    /// The index of the mount that provided this directory.
+    pub(crate) mount: usize,
+    /// The directory's contents, keyed and sorted by name.
+    pub(crate) children: BTreeMap<String, Entry>,
+}
+
+/// A file that will be embedded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct File {
+    /// The path relative to the embedded root, using `/` as a separator.
+    pub(crate) path: String,
+    /// Where the file's contents can be read from.
+    pub(crate) source: PathBuf,
+    /// The index of the mount that provided this file.
+    pub(crate) mount: usize,
+    /// The file's contents after being transformed (e.g. by
+    /// `normalize_line_endings`), if they differ from what is on disk.
+    pub(crate) contents: Option<Vec<u8>>,
+    /// Is the file known to be valid UTF-8?
+    pub(crate) text: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Entry {
+    Dir(Dir),
+    File(File),
+}
+
+impl Entry {
+    pub(crate) fn path(&self) -> &str {
+        match self {
+            Entry::Dir(d) => &d.path,
+            Entry::File(f) => &f.path,
+        }
+    }
+
+    pub(crate) fn source(&self) -> &PathBuf {
+        match self {
+            Entry::Dir(d) => &d.source,
+            Entry::File(f) => &f.source,
+        }
+    }
+
+    pub(crate) fn mount(&self) -> usize {
+        match self {
+            Entry::Dir(d) => d.mount,
+            Entry::File(f) => f.mount,
+        }
+    }
+}
+
+impl File {
+    /// The contents that will be embedded.
+    pub(crate) fn read(&self) -> Cow<'_, [u8]> {
+        match &self.contents {
+            Some(contents) => Cow::Borrowed(contents),
+            None => Cow::Owned(crate::read_file(&self.source)),
+        }
+    }
+
+    /// The 64-bit FNV-1a hash of the contents that will be embedded, read a
+    /// chunk at a time when they haven't been transformed.
+    pub(crate) fn hash(&self) -> u64 {
+        match &self.contents {
+            Some(contents) => fnv1a(contents),
+            None => {
+                crate::track_path(&self.source);
+                let mut hasher = Fnv1a::default();
+                fs::File::open(&self.source)
+                    .and_then(|mut f| io::copy(&mut f, &mut hasher))
+                    .unwrap_or_else(|e| {
+                        panic!("Unable to read \"{}\": {}", self.source.display(), e)
+                    });
+                hasher.0
+            }
+        }
+    }
+
+    /// The file's size in bytes, or `0` if it can't be read.
+    pub(crate) fn len(&self) -> u64 {
+        match &self.contents {
+            Some(contents) => contents.len() as u64,
+            None => self.source.metadata().map(|m| m.len()).unwrap_or(0),
+        }
+    }
+}
+
+/// Two mounts tried to provide the same path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Conflict {
+    pub(crate) path: String,
+    pub(crate) existing: PathBuf,
+    pub(crate) replacement: PathBuf,
+    /// The mount providing the replacement.
+    pub(crate) mount: usize,
+}
+
+impl Dir {
+    /// The number of files in this directory and its sub-directories, and
+    /// their combined size in bytes.
+    pub(crate) fn stats(&self) -> (usize, u64) {
+        self.children
+            .values()
+            .fold((0, 0), |(count, size), child| match child {
+                Entry::Dir(d) => {
+                    let (c, s) = d.stats();
+                    (count + c, size + s)
+                }
+                Entry::File(f) => (count + 1, size + f.len()),
+            })
+    }
+
+    /// Wrap this directory in parents until it sits at the root, so it can
+    /// be merged with other mounts.
+    pub(crate) fn into_root(self) -> Dir {
+        let mut dir = self;
+
+        while !dir.path.is_empty() {
+            let (parent, name) = match dir.path.rfind('/') {
+                Some(ix) => (dir.path[..ix].to_string(), dir.path[ix + 1..].to_string()),
+                None => (String::new(), dir.path.clone()),
+            };
+
+            let mut children = BTreeMap::new();
+            let source = dir.source.clone();
+            let mount = dir.mount;
+            children.insert(name, Entry::Dir(dir));
+
+            dir = Dir {
+                path: parent,
+                source,
+                mount,
+                children,
+            };
+        }
+
+        dir
+    }
+
+    /// Merge the contents of `other` into this directory, recursively merging
+    /// sub-directories which exist in both.
+    pub(crate) fn merge(&mut self, other: Dir, on_conflict: OnConflict) -> Result<(), Conflict> {
+        for (name, entry) in other.children {
+            let existing = match self.children.get_mut(&name) {
+                Some(existing) => existing,
+                None => {
+                    self.children.insert(name, entry);
+                    continue;
+                }
+            };
+
+            match (existing, entry) {
+                (Entry::Dir(existing), Entry::Dir(dir)) => existing.merge(dir, on_conflict)?,
+                (existing, entry) => match on_conflict {
+                    OnConflict::First => {}
+                    OnConflict::Last => *existing = entry,
+                    OnConflict::Error => {
+                        return Err(Conflict {
+                            path: entry.path().to_string(),
+                            existing: existing.source().clone(),
+                            replacement: entry.source().clone(),
+                            mount: entry.mount(),
+                        })
+                    }
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, mount: usize) -> Entry {
+        Entry::File(File {
+            path: path.to_string(),
+            source: PathBuf::from(format!("mount-{}/{}", mount, path)),
+            mount,
+            contents: None,
+            text: false,
+        })
+    }
+
+    fn dir(path: &str, mount: usize, children: Vec<Entry>) -> Dir {
+        Dir {
+            path: path.to_string(),
+            source: PathBuf::from(format!("mount-{}/{}", mount, path)),
+            mount,
+            children: children
+                .into_iter()
+                .map(|e| (e.path().rsplit('/').next().unwrap().to_string(), e))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn nest_a_mount_under_the_root() {
+        let vendor = dir("vendor/js", 1, vec![file("vendor/js/app.js", 1)]);
+
+        let root = vendor.clone().into_root();
+
+        assert_eq!(root.path, "");
+        let vendor_dir = match &root.children["vendor"] {
+            Entry::Dir(d) => d,
+            Entry::File(_) => unreachable!(),
+        };
+        assert_eq!(vendor_dir.path, "vendor");
+        assert_eq!(vendor_dir.children["js"], Entry::Dir(vendor));
+    }
+
+    #[test]
+    fn merge_disjoint_trees() {
+        let mut first = dir("", 0, vec![Entry::Dir(dir("a", 0, vec![file("a/1", 0)]))]);
+        let second = dir("", 1, vec![Entry::Dir(dir("a", 1, vec![file("a/2", 1)]))]);
+
+        first.merge(second, OnConflict::Error).unwrap();
+
+        let expected = dir(
+            "",
+            0,
+            vec![Entry::Dir(dir(
+                "a",
+                0,
+                vec![file("a/1", 0), file("a/2", 1)],
+            ))],
+        );
+        assert_eq!(first, expected);
+    }
+
+    #[test]
+    fn conflicting_files() {
+        let first = dir("", 0, vec![file("index.html", 0)]);
+        let second = dir("", 1, vec![file("index.html", 1)]);
+
+        let err = first
+            .clone()
+            .merge(second.clone(), OnConflict::Error)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Conflict {
+                path: "index.html".to_string(),
+                existing: PathBuf::from("mount-0/index.html"),
+                replacement: PathBuf::from("mount-1/index.html"),
+                mount: 1,
+            }
+        );
+
+        let mut first_wins = first.clone();
+        first_wins.merge(second.clone(), OnConflict::First).unwrap();
+        assert_eq!(first_wins, first);
+
+        let mut last_wins = first;
+        last_wins.merge(second, OnConflict::Last).unwrap();
+        assert_eq!(last_wins.children["index.html"], file("index.html", 1));
+    }
+}
@@ -0,0 +1,130 @@
+//! Checking that embedded data files are well-formed when `validate_syntax`
+//! is enabled.
+
+use crate::{options::Options, tree, Error};
+
+/// Make sure every file with a known extension can be parsed.
+///
+/// It is an error for a file to use a format whose feature flag is disabled,
+/// because it couldn't be checked.
+pub(crate) fn validate_syntax(options: &Options, dir: &tree::Dir) -> Result<(), Error> {
+    for child in dir.children.values() {
+        match child {
+            tree::Entry::Dir(d) => validate_syntax(options, d)?,
+            tree::Entry::File(f) => validate_file(options, f)?,
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_file(options: &Options, file: &tree::File) -> Result<(), Error> {
+    let extension = file.source.extension().and_then(|ext| ext.to_str());
+
+    let result = match extension {
+        Some("json") => check_json(&file.read()),
+        Some("toml") => check_toml(&file.read()),
+        Some("yaml") | Some("yml") => check_yaml(&file.read()),
+        _ => Ok(()),
+    };
+
+    let message = match result {
+        Ok(()) => return Ok(()),
+        Err(Invalid::Syntax(e)) => format!("Unable to parse \"{}\": {}", file.path, e),
+        Err(Invalid::MissingFeature(feature)) => format!(
+            "Unable to check \"{}\" because the \"{}\" feature isn't enabled",
+            file.path, feature
+        ),
+    };
+
+    Err(Error::new(options.mounts[file.mount].span, message))
+}
+
+/// Why a file couldn't be validated.
+// which variants are constructed depends on the enabled features
+#[allow(dead_code)]
+#[derive(Debug)]
+enum Invalid {
+    Syntax(String),
+    /// The feature needed to parse this format is disabled.
+    MissingFeature(&'static str),
+}
+
+#[cfg(feature = "json")]
+fn check_json(contents: &[u8]) -> Result<(), Invalid> {
+    serde_json::from_slice::<serde_json::Value>(contents)
+        .map(|_| ())
+        .map_err(|e| Invalid::Syntax(e.to_string()))
+}
+
+#[cfg(not(feature = "json"))]
+fn check_json(_contents: &[u8]) -> Result<(), Invalid> {
+    Err(Invalid::MissingFeature("json"))
+}
+
+#[cfg(feature = "toml")]
+fn check_toml(contents: &[u8]) -> Result<(), Invalid> {
+    let text = std::str::from_utf8(contents).map_err(|e| Invalid::Syntax(e.to_string()))?;
+
+    toml::from_str::<toml::Table>(text)
+        .map(|_| ())
+        .map_err(|e| Invalid::Syntax(e.to_string()))
+}
+
+#[cfg(not(feature = "toml"))]
+fn check_toml(_contents: &[u8]) -> Result<(), Invalid> {
+    Err(Invalid::MissingFeature("toml"))
+}
+
+#[cfg(feature = "yaml")]
+fn check_yaml(contents: &[u8]) -> Result<(), Invalid> {
+    serde_yaml::from_slice::<serde_yaml::Value>(contents)
+        .map(|_| ())
+        .map_err(|e| Invalid::Syntax(e.to_string()))
+}
+
+#[cfg(not(feature = "yaml"))]
+fn check_yaml(_contents: &[u8]) -> Result<(), Invalid> {
+    Err(Invalid::MissingFeature("yaml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn json() {
+        assert!(check_json(br#"{"name": "value"}"#).is_ok());
+        assert!(check_json(br#"{"name": "#).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "toml")]
+    fn toml() {
+        assert!(check_toml(b"name = \"value\"").is_ok());
+        assert!(check_toml(b"name = ").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn yaml() {
+        assert!(check_yaml(b"name: value").is_ok());
+        assert!(check_yaml(b"name: [value").is_err());
+    }
+
+    #[test]
+    fn formats_without_their_feature_are_errors() {
+        for (enabled, result) in [
+            (cfg!(feature = "json"), check_json(b"{}")),
+            (cfg!(feature = "toml"), check_toml(b"")),
+            (cfg!(feature = "yaml"), check_yaml(b"{}")),
+        ] {
+            match result {
+                Ok(()) => assert!(enabled),
+                Err(Invalid::MissingFeature(_)) => assert!(!enabled),
+                Err(Invalid::Syntax(e)) => panic!("{}", e),
+            }
+        }
+    }
+}
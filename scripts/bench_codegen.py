@@ -0,0 +1,157 @@
+#!/usr/bin/env python3
+"""Measure how long the compiler takes, and how much memory it uses, to
+embed a large directory using `include_dir!()` and `include_archive!()`.
+
+    python3 scripts/bench_codegen.py [--runs N] [--release]
+
+The fixtures are generated deterministically in a temporary directory, along
+with a tiny binary crate for each strategy which depends on this checkout of
+`include_dir`. Dependencies are built once up front, so each measurement is
+just the time taken to recompile the crate containing the macro invocation.
+Incremental compilation is disabled, otherwise rustc could reuse the
+evaluated constants from the previous build.
+Peak RAM is the largest resident set size of any process cargo waited on
+(i.e. rustc).
+"""
+
+import argparse
+import os
+import shutil
+import subprocess
+import sys
+import tempfile
+from pathlib import Path
+
+REPO = Path(__file__).resolve().parent.parent
+ENV = dict(os.environ, CARGO_INCREMENTAL="0")
+
+FIXTURES = [
+    # (name, number of directories, files per directory, bytes per file)
+    ("5,000 files, 20 MB in 50 dirs", 50, 100, 4096),
+    ("20,000 files, 2 MB in 400 dirs", 400, 50, 100),
+]
+
+STRATEGIES = [
+    # (name, static declaration, how to count the top-level entries)
+    (
+        "`include_dir!()`",
+        'static ASSETS: Dir<\'_> = include_dir!("{path}");',
+        "ASSETS.entries().len()",
+    ),
+    (
+        "`include_archive!()`",
+        'static ASSETS: &[u8] = include_archive!("{path}");',
+        "ArchiveDir::from_bytes(ASSETS).unwrap().entries().count()",
+    ),
+]
+
+
+def generate_fixture(root, dirs, files_per_dir, size):
+    """Fill `root` with files whose contents depend only on their index."""
+    for d in range(dirs):
+        directory = root / f"dir_{d:04}"
+        directory.mkdir(parents=True)
+        for f in range(files_per_dir):
+            line = f"dir {d} file {f}\n".encode()
+            contents = (line * (size // len(line) + 1))[:size]
+            (directory / f"file_{f:04}.txt").write_bytes(contents)
+
+
+def write_crate(crate, fixture, declaration, count):
+    (crate / "src").mkdir(parents=True)
+    (crate / "Cargo.toml").write_text(
+        f"""[package]
+name = "bench"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+include_dir = {{ path = {str(REPO / "include_dir")!r} }}
+
+[workspace]
+"""
+    )
+    declaration = declaration.format(path=fixture.as_posix())
+    (crate / "src" / "main.rs").write_text(
+        f"""use include_dir::*;
+
+{declaration}
+
+fn main() {{
+    println!("{{}}", {count});
+}}
+"""
+    )
+
+
+def cargo_build(crate, target_dir, release):
+    args = ["cargo", "build", "--quiet", "--target-dir", str(target_dir)]
+    if release:
+        args.append("--release")
+    subprocess.run(args, cwd=crate, check=True, env=ENV)
+
+
+def measure(crate, target_dir, release):
+    """Recompile the crate, returning the time taken and the peak RSS in MB."""
+    (crate / "src" / "main.rs").touch()
+
+    # ru_maxrss is the largest of any child we've waited on so far, so each
+    # measurement needs a fresh process to start from zero
+    script = (
+        "import resource, subprocess, sys, time\n"
+        "start = time.perf_counter()\n"
+        "subprocess.run(sys.argv[1:], check=True)\n"
+        "elapsed = time.perf_counter() - start\n"
+        "rss = resource.getrusage(resource.RUSAGE_CHILDREN).ru_maxrss\n"
+        "print(elapsed, rss)\n"
+    )
+    args = ["cargo", "build", "--quiet", "--target-dir", str(target_dir)]
+    if release:
+        args.append("--release")
+    output = subprocess.run(
+        [sys.executable, "-c", script, *args],
+        cwd=crate,
+        env=ENV,
+        check=True,
+        capture_output=True,
+        text=True,
+    ).stdout
+    elapsed, rss_kb = output.split()
+    return float(elapsed), int(rss_kb) / 1024
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--runs", type=int, default=3, help="builds per measurement")
+    parser.add_argument("--release", action="store_true", help="measure release builds")
+    args = parser.parse_args()
+
+    with tempfile.TemporaryDirectory(prefix="include-dir-bench-") as tmp:
+        tmp = Path(tmp)
+        target_dir = tmp / "target"
+
+        print("| Fixture | Strategy | Time | Peak RAM |")
+        print("|---------|----------|------|----------|")
+
+        for name, dirs, files_per_dir, size in FIXTURES:
+            fixture = tmp / "fixtures" / f"{dirs}x{files_per_dir}x{size}"
+            generate_fixture(fixture, dirs, files_per_dir, size)
+
+            for i, (strategy, declaration, count) in enumerate(STRATEGIES):
+                crate = tmp / "crates" / f"{fixture.name}-{i}"
+                write_crate(crate, fixture, declaration, count)
+                cargo_build(crate, target_dir, args.release)
+
+                results = [measure(crate, target_dir, args.release) for _ in range(args.runs)]
+                elapsed = min(r[0] for r in results)
+                rss = min(r[1] for r in results)
+
+                label = name if i == 0 else ""
+                print(f"| {label} | {strategy} | {elapsed:.1f}s | {rss:.0f}MB |", flush=True)
+
+            shutil.rmtree(fixture)
+
+
+if __name__ == "__main__":
+    main()